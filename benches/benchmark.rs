@@ -13,8 +13,8 @@
 // limitations under the License.
 
 use criterion::{black_box, criterion_group, criterion_main, Benchmark, Criterion};
-use noisy_float::prelude::*;
-use std::iter::Sum;
+use noisy_float::{prelude::*, sort::radix_sort};
+use std::{cmp::Ordering, iter::Sum};
 
 fn bench_ops(c: &mut Criterion) {
     c.bench(
@@ -106,5 +106,174 @@ fn bench_algorithm(c: &mut Criterion) {
     );
 }
 
-criterion_group!(benches, bench_ops, bench_algorithm);
+fn bench_sort(c: &mut Criterion) {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let values: Vec<N64> = (0..100_000)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            n64((state >> 11) as f64 - (1_u64 << 52) as f64)
+        })
+        .collect();
+    let unstable_values = values.clone();
+    let radix_values = values.clone();
+    #[allow(unused_mut)]
+    let mut benchmark = Benchmark::new("sort_unstable", move |b| {
+        b.iter_with_setup(|| unstable_values.clone(), |mut v| v.sort_unstable())
+    })
+    .with_function("radix_sort", move |b| {
+        b.iter_with_setup(|| radix_values.clone(), |mut v| radix_sort(&mut v))
+    });
+    #[cfg(feature = "rayon")]
+    {
+        benchmark = benchmark.with_function("par_radix_sort", move |b| {
+            b.iter_with_setup(
+                || values.clone(),
+                |mut v| noisy_float::sort::par_radix_sort(&mut v),
+            )
+        });
+    }
+    c.bench("Sort [100000 x N64]", benchmark);
+}
+
+/// The previous `Ord::cmp` implementation, a chain of `<` and `==`,
+/// kept as a reference point for the comparison benchmarks.
+fn chained_cmp(a: &R64, b: &R64) -> Ordering {
+    if a.raw() < b.raw() {
+        Ordering::Less
+    } else if a.raw() == b.raw() {
+        Ordering::Equal
+    } else {
+        Ordering::Greater
+    }
+}
+
+/// `Ord::max` as previously derived from `chained_cmp`.
+fn chained_max(a: R64, b: R64) -> R64 {
+    match chained_cmp(&a, &b) {
+        Ordering::Greater => a,
+        _ => b,
+    }
+}
+
+/// `Ord::min` as previously derived from `chained_cmp`.
+fn chained_min(a: R64, b: R64) -> R64 {
+    match chained_cmp(&a, &b) {
+        Ordering::Greater => b,
+        _ => a,
+    }
+}
+
+fn bench_compare(c: &mut Criterion) {
+    let values: Vec<R64> = (0..1000)
+        .map(|i| r64(((i * 7919) % 1000) as f64 - 500.0))
+        .collect();
+    let raw: Vec<f64> = values.iter().map(|x| x.raw()).collect();
+
+    let (v, r) = (values.clone(), raw.clone());
+    c.bench(
+        "Max [1000 values]",
+        Benchmark::new("f64", move |b| {
+            b.iter(|| black_box(&r).iter().fold(f64::MIN, |a, &x| a.max(x)))
+        })
+        .with_function("R64", move |b| {
+            b.iter(|| black_box(&v).iter().fold(R64::MIN, |a, &x| a.max(x)))
+        })
+        .with_function("R64 (chained cmp)", {
+            let v = values.clone();
+            move |b| {
+                b.iter(|| {
+                    black_box(&v)
+                        .iter()
+                        .fold(R64::MIN, |a, &x| chained_max(a, x))
+                })
+            }
+        }),
+    );
+
+    let (v, r) = (values.clone(), raw.clone());
+    c.bench(
+        "Min [1000 values]",
+        Benchmark::new("f64", move |b| {
+            b.iter(|| black_box(&r).iter().fold(f64::MAX, |a, &x| a.min(x)))
+        })
+        .with_function("R64", move |b| {
+            b.iter(|| black_box(&v).iter().fold(R64::MAX, |a, &x| a.min(x)))
+        })
+        .with_function("R64 (chained cmp)", {
+            let v = values.clone();
+            move |b| {
+                b.iter(|| {
+                    black_box(&v)
+                        .iter()
+                        .fold(R64::MAX, |a, &x| chained_min(a, x))
+                })
+            }
+        }),
+    );
+
+    let (v, r) = (values.clone(), raw.clone());
+    c.bench(
+        "Clamp [1000 values]",
+        Benchmark::new("f64", move |b| {
+            b.iter(|| {
+                black_box(&r)
+                    .iter()
+                    .map(|&x| x.clamp(-100.0, 100.0))
+                    .sum::<f64>()
+            })
+        })
+        .with_function("R64", move |b| {
+            b.iter(|| {
+                black_box(&v)
+                    .iter()
+                    .map(|&x| x.clamp(r64(-100.0), r64(100.0)))
+                    .sum::<R64>()
+            })
+        })
+        .with_function("R64 (chained cmp)", {
+            let v = values.clone();
+            move |b| {
+                b.iter(|| {
+                    black_box(&v)
+                        .iter()
+                        .map(|&x| chained_min(chained_max(x, r64(-100.0)), r64(100.0)))
+                        .sum::<R64>()
+                })
+            }
+        }),
+    );
+
+    let v = values.clone();
+    c.bench(
+        "Compare [1000 pairs]",
+        Benchmark::new("R64", move |b| {
+            b.iter(|| {
+                let v = black_box(&v);
+                v.iter()
+                    .zip(&v[1..])
+                    .map(|(a, b)| a.cmp(b) as i32)
+                    .sum::<i32>()
+            })
+        })
+        .with_function("R64 (chained cmp)", move |b| {
+            b.iter(|| {
+                let v = black_box(&values);
+                v.iter()
+                    .zip(&v[1..])
+                    .map(|(a, b)| chained_cmp(a, b) as i32)
+                    .sum::<i32>()
+            })
+        }),
+    );
+}
+
+criterion_group!(
+    benches,
+    bench_ops,
+    bench_algorithm,
+    bench_sort,
+    bench_compare
+);
 criterion_main!(benches);
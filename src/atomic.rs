@@ -0,0 +1,404 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{FloatChecker, NoisyFloat, RawFloat};
+#[cfg(target_has_atomic = "32")]
+use core::sync::atomic::AtomicU32;
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::AtomicU64;
+use core::{fmt, marker::PhantomData, sync::atomic::Ordering};
+
+/// Trait for floating point types that can be stored in an atomic integer.
+///
+/// This is implemented for `f32` and `f64` on targets that support
+/// atomic operations on 32- and 64-bit integers respectively.
+/// The methods mirror those of the atomic integer types on the bits of the float.
+pub trait AtomicFloat: RawFloat {
+    /// The atomic integer type holding the bits of the float.
+    type Atomic: Send + Sync;
+
+    /// Creates a new atomic integer holding the given bits.
+    fn new_atomic(bits: Self::Bits) -> Self::Atomic;
+
+    /// Consumes the atomic integer and returns the bits it holds.
+    fn into_bits(atomic: Self::Atomic) -> Self::Bits;
+
+    /// Loads the bits from the atomic integer.
+    fn load(atomic: &Self::Atomic, order: Ordering) -> Self::Bits;
+
+    /// Stores the bits into the atomic integer.
+    fn store(atomic: &Self::Atomic, bits: Self::Bits, order: Ordering);
+
+    /// Stores the bits into the atomic integer, returning the previous bits.
+    fn swap(atomic: &Self::Atomic, bits: Self::Bits, order: Ordering) -> Self::Bits;
+
+    /// Stores `new` into the atomic integer if it holds `current`.
+    fn compare_exchange(
+        atomic: &Self::Atomic,
+        current: Self::Bits,
+        new: Self::Bits,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Self::Bits, Self::Bits>;
+
+    /// Stores `new` into the atomic integer if it holds `current`,
+    /// but may spuriously fail even when the comparison succeeds.
+    fn compare_exchange_weak(
+        atomic: &Self::Atomic,
+        current: Self::Bits,
+        new: Self::Bits,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Self::Bits, Self::Bits>;
+}
+
+macro_rules! impl_atomic_float {
+    ($float:ty, $bits:ty, $atomic:ty, $width:literal) => {
+        #[cfg(target_has_atomic = $width)]
+        impl AtomicFloat for $float {
+            type Atomic = $atomic;
+
+            #[inline]
+            fn new_atomic(bits: $bits) -> $atomic {
+                <$atomic>::new(bits)
+            }
+
+            #[inline]
+            fn into_bits(atomic: $atomic) -> $bits {
+                atomic.into_inner()
+            }
+
+            #[inline]
+            fn load(atomic: &$atomic, order: Ordering) -> $bits {
+                atomic.load(order)
+            }
+
+            #[inline]
+            fn store(atomic: &$atomic, bits: $bits, order: Ordering) {
+                atomic.store(bits, order)
+            }
+
+            #[inline]
+            fn swap(atomic: &$atomic, bits: $bits, order: Ordering) -> $bits {
+                atomic.swap(bits, order)
+            }
+
+            #[inline]
+            fn compare_exchange(
+                atomic: &$atomic,
+                current: $bits,
+                new: $bits,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<$bits, $bits> {
+                atomic.compare_exchange(current, new, success, failure)
+            }
+
+            #[inline]
+            fn compare_exchange_weak(
+                atomic: &$atomic,
+                current: $bits,
+                new: $bits,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<$bits, $bits> {
+                atomic.compare_exchange_weak(current, new, success, failure)
+            }
+        }
+    };
+}
+
+impl_atomic_float!(f32, u32, AtomicU32, "32");
+impl_atomic_float!(f64, u64, AtomicU64, "64");
+
+/// A `NoisyFloat` which can be safely shared between threads.
+///
+/// The value is stored as the bits of the float in an atomic integer,
+/// so it has the same in-memory representation as the underlying float.
+/// Typical users will not need to access this struct directly, but
+/// can instead use the type aliases found in the module `noisy_float::types`,
+/// such as `AtomicR64`.
+///
+/// Operations that compute a new value from the current one, such as
+/// `fetch_add`, are implemented with a compare-and-swap loop, and the new
+/// value is validated by the `FloatChecker` like any other `NoisyFloat`.
+/// Since the comparison of `compare_exchange` is on the bits of the values,
+/// `-0.0` and `+0.0` are considered different there.
+///
+/// ```
+/// use noisy_float::prelude::*;
+/// use std::sync::atomic::Ordering;
+///
+/// let total = AtomicR64::new(r64(1.5));
+/// total.fetch_add(r64(2.0), Ordering::Relaxed);
+/// total.fetch_max(r64(3.0), Ordering::Relaxed);
+/// assert_eq!(total.load(Ordering::Relaxed), r64(3.5));
+/// ```
+#[repr(transparent)]
+pub struct AtomicNoisyFloat<F: AtomicFloat, C: FloatChecker<F>> {
+    atomic: F::Atomic,
+    checker: PhantomData<C>,
+}
+
+impl<F: AtomicFloat, C: FloatChecker<F>> AtomicNoisyFloat<F, C> {
+    /// Creates a new atomic holding the given value.
+    #[inline]
+    pub fn new(value: NoisyFloat<F, C>) -> Self {
+        AtomicNoisyFloat {
+            atomic: F::new_atomic(value.to_bits()),
+            checker: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn from_bits(bits: F::Bits) -> NoisyFloat<F, C> {
+        // The atomic only ever holds the bits of valid values.
+        NoisyFloat::new_unchecked(F::from_bits(bits))
+    }
+
+    /// Consumes the atomic and returns the contained value.
+    #[inline]
+    pub fn into_inner(self) -> NoisyFloat<F, C> {
+        Self::from_bits(F::into_bits(self.atomic))
+    }
+
+    /// Loads the value.
+    ///
+    /// See `AtomicU64::load` for the possible orderings.
+    #[inline]
+    pub fn load(&self, order: Ordering) -> NoisyFloat<F, C> {
+        Self::from_bits(F::load(&self.atomic, order))
+    }
+
+    /// Stores a value.
+    ///
+    /// See `AtomicU64::store` for the possible orderings.
+    #[inline]
+    pub fn store(&self, value: NoisyFloat<F, C>, order: Ordering) {
+        F::store(&self.atomic, value.to_bits(), order)
+    }
+
+    /// Stores a value, returning the previous value.
+    #[inline]
+    pub fn swap(&self, value: NoisyFloat<F, C>, order: Ordering) -> NoisyFloat<F, C> {
+        Self::from_bits(F::swap(&self.atomic, value.to_bits(), order))
+    }
+
+    /// Stores `new` if the current value has the same bits as `current`.
+    ///
+    /// Returns the previous value, wrapped in `Ok` if it was replaced
+    /// and in `Err` otherwise.
+    /// See `AtomicU64::compare_exchange` for the possible orderings.
+    #[inline]
+    pub fn compare_exchange(
+        &self,
+        current: NoisyFloat<F, C>,
+        new: NoisyFloat<F, C>,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<NoisyFloat<F, C>, NoisyFloat<F, C>> {
+        F::compare_exchange(
+            &self.atomic,
+            current.to_bits(),
+            new.to_bits(),
+            success,
+            failure,
+        )
+        .map(Self::from_bits)
+        .map_err(Self::from_bits)
+    }
+
+    /// Stores `new` if the current value has the same bits as `current`,
+    /// but may spuriously fail even when the comparison succeeds.
+    ///
+    /// See `AtomicU64::compare_exchange_weak` for the possible orderings.
+    #[inline]
+    pub fn compare_exchange_weak(
+        &self,
+        current: NoisyFloat<F, C>,
+        new: NoisyFloat<F, C>,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<NoisyFloat<F, C>, NoisyFloat<F, C>> {
+        F::compare_exchange_weak(
+            &self.atomic,
+            current.to_bits(),
+            new.to_bits(),
+            success,
+            failure,
+        )
+        .map(Self::from_bits)
+        .map_err(Self::from_bits)
+    }
+
+    /// Replaces the value by the result of `f`, retrying until no other
+    /// thread changed the value in the meantime, or until `f` returns `None`.
+    ///
+    /// Returns the previous value, wrapped in `Ok` if it was replaced
+    /// and in `Err` otherwise.
+    /// See `AtomicU64::fetch_update` for the possible orderings.
+    #[inline]
+    pub fn fetch_update<U>(
+        &self,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        mut f: U,
+    ) -> Result<NoisyFloat<F, C>, NoisyFloat<F, C>>
+    where
+        U: FnMut(NoisyFloat<F, C>) -> Option<NoisyFloat<F, C>>,
+    {
+        let mut previous = self.load(fetch_order);
+        while let Some(next) = f(previous) {
+            match self.compare_exchange_weak(previous, next, set_order, fetch_order) {
+                Ok(value) => return Ok(value),
+                Err(value) => previous = value,
+            }
+        }
+        Err(previous)
+    }
+
+    #[inline]
+    fn fetch_apply<U>(&self, order: Ordering, mut f: U) -> NoisyFloat<F, C>
+    where
+        U: FnMut(NoisyFloat<F, C>) -> NoisyFloat<F, C>,
+    {
+        let fetch_order = match order {
+            Ordering::AcqRel => Ordering::Acquire,
+            Ordering::Release => Ordering::Relaxed,
+            order => order,
+        };
+        match self.fetch_update(order, fetch_order, |value| Some(f(value))) {
+            Ok(value) | Err(value) => value,
+        }
+    }
+
+    /// Adds to the value, returning the previous value.
+    ///
+    /// The sum is validated by the `FloatChecker`, like for `NoisyFloat`.
+    #[inline]
+    pub fn fetch_add(&self, value: NoisyFloat<F, C>, order: Ordering) -> NoisyFloat<F, C> {
+        self.fetch_apply(order, |current| current + value)
+    }
+
+    /// Subtracts from the value, returning the previous value.
+    ///
+    /// The difference is validated by the `FloatChecker`, like for `NoisyFloat`.
+    #[inline]
+    pub fn fetch_sub(&self, value: NoisyFloat<F, C>, order: Ordering) -> NoisyFloat<F, C> {
+        self.fetch_apply(order, |current| current - value)
+    }
+
+    /// Replaces the value by the maximum of it and the given value,
+    /// returning the previous value.
+    #[inline]
+    pub fn fetch_max(&self, value: NoisyFloat<F, C>, order: Ordering) -> NoisyFloat<F, C> {
+        self.fetch_apply(order, |current| current.max(value))
+    }
+
+    /// Replaces the value by the minimum of it and the given value,
+    /// returning the previous value.
+    #[inline]
+    pub fn fetch_min(&self, value: NoisyFloat<F, C>, order: Ordering) -> NoisyFloat<F, C> {
+        self.fetch_apply(order, |current| current.min(value))
+    }
+}
+
+impl<F: AtomicFloat, C: FloatChecker<F>> From<NoisyFloat<F, C>> for AtomicNoisyFloat<F, C> {
+    #[inline]
+    fn from(value: NoisyFloat<F, C>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<F: AtomicFloat + Default, C: FloatChecker<F>> Default for AtomicNoisyFloat<F, C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(NoisyFloat::default())
+    }
+}
+
+impl<F: AtomicFloat + fmt::Debug, C: FloatChecker<F>> fmt::Debug for AtomicNoisyFloat<F, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        fmt::Debug::fmt(&self.load(Ordering::Relaxed), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use crate::prelude::*;
+    use std::format;
+
+    #[test]
+    fn atomic() {
+        use std::sync::atomic::Ordering::{Relaxed, SeqCst};
+
+        let atomic = AtomicR64::new(r64(1.0));
+        assert_eq!(atomic.load(Relaxed), r64(1.0));
+        atomic.store(r64(2.0), Relaxed);
+        assert_eq!(atomic.swap(r64(3.0), SeqCst), r64(2.0));
+        assert_eq!(
+            atomic.compare_exchange(r64(3.0), r64(4.0), SeqCst, Relaxed),
+            Ok(r64(3.0))
+        );
+        assert_eq!(
+            atomic.compare_exchange(r64(3.0), r64(5.0), SeqCst, Relaxed),
+            Err(r64(4.0))
+        );
+        assert_eq!(atomic.fetch_add(r64(0.5), SeqCst), r64(4.0));
+        assert_eq!(atomic.fetch_sub(r64(1.5), SeqCst), r64(4.5));
+        assert_eq!(atomic.fetch_max(r64(10.0), SeqCst), r64(3.0));
+        assert_eq!(atomic.fetch_min(r64(-1.0), SeqCst), r64(10.0));
+        assert_eq!(
+            atomic.fetch_update(SeqCst, SeqCst, |value| Some(value * 3.0)),
+            Ok(r64(-1.0))
+        );
+        assert_eq!(
+            atomic.fetch_update(SeqCst, SeqCst, |_| None),
+            Err(r64(-3.0))
+        );
+        assert_eq!(atomic.into_inner(), r64(-3.0));
+        assert_eq!(format!("{:?}", AtomicN32::new(n32(0.5))), "0.5");
+        assert_eq!(AtomicN32::default().load(Relaxed), n32(0.0));
+    }
+
+    #[test]
+    fn atomic_across_threads() {
+        use std::{sync::atomic::Ordering::Relaxed, thread};
+
+        let total = AtomicR32::new(r32(0.0));
+        let max = AtomicN64::new(N64::NEG_INFINITY);
+        thread::scope(|scope| {
+            for i in 0..4 {
+                let (total, max) = (&total, &max);
+                scope.spawn(move || {
+                    for j in 0..1000 {
+                        total.fetch_add(r32(1.0), Relaxed);
+                        max.fetch_max(n64((i * 1000 + j) as f64), Relaxed);
+                    }
+                });
+            }
+        });
+        assert_eq!(total.load(Relaxed), r32(4000.0));
+        assert_eq!(max.load(Relaxed), n64(3999.0));
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic]
+    fn atomic_fetch_add_overflow() {
+        let atomic = AtomicR64::new(R64::MAX);
+        atomic.fetch_add(R64::MAX, std::sync::atomic::Ordering::Relaxed);
+    }
+}
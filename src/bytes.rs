@@ -0,0 +1,409 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Conversions between `NoisyFloat` and its byte representation.
+
+use crate::{FloatChecker, InvalidValueError, NoisyFloat, RawFloat};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt};
+use num_traits::{FromBytes, ToBytes};
+
+/// The error returned when decoding a byte slice into a `Vec` of `NoisyFloat`s fails.
+///
+/// With the `std` feature enabled, this implements `std::error::Error`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FromBytesError {
+    /// The value starting at the given byte offset is rejected by the `FloatChecker`.
+    InvalidValue {
+        /// The byte offset of the first invalid value.
+        offset: usize,
+    },
+    /// The length of the byte slice is not a multiple of the size of the float type.
+    TrailingBytes {
+        /// The byte offset of the incomplete value at the end of the slice.
+        offset: usize,
+    },
+}
+
+impl FromBytesError {
+    /// Returns the byte offset at which decoding failed.
+    pub fn offset(self) -> usize {
+        match self {
+            FromBytesError::InvalidValue { offset } | FromBytesError::TrailingBytes { offset } => {
+                offset
+            }
+        }
+    }
+}
+
+impl fmt::Display for FromBytesError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            FromBytesError::InvalidValue { offset } => {
+                write!(f, "illegal value at byte offset {}", offset)
+            }
+            FromBytesError::TrailingBytes { offset } => {
+                write!(f, "incomplete value at byte offset {}", offset)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FromBytesError {}
+
+/// A byte string whose lexicographic order matches the order of `NoisyFloat` values.
+///
+/// This is the encoding produced by `to_ordered_bytes`, for use as a key in
+/// storage that sorts keys by their bytes, such as key-value stores.
+/// `-0.0` and `+0.0` are encoded the same, like they compare equal.
+/// The size of the key is the size of the float type, e.g. an
+/// `OrderedKey<4>` holds an `f32` based value and an `OrderedKey<8>`
+/// an `f64` based value.
+///
+/// ```
+/// use noisy_float::{prelude::*, OrderedKey};
+/// use std::convert::TryFrom;
+///
+/// let low = OrderedKey::from(r64(-1.5));
+/// let high = OrderedKey::from(r64(0.25));
+/// assert!(low.as_bytes() < high.as_bytes());
+/// assert_eq!(R64::try_from(high), Ok(r64(0.25)));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OrderedKey<const N: usize>([u8; N]);
+
+impl<const N: usize> OrderedKey<N> {
+    /// Wraps bytes previously obtained from `to_bytes` or `to_ordered_bytes`.
+    ///
+    /// The bytes are validated when converting the key back into a `NoisyFloat`.
+    #[inline]
+    pub fn from_bytes(bytes: [u8; N]) -> Self {
+        OrderedKey(bytes)
+    }
+
+    /// Returns the bytes of the key.
+    #[inline]
+    pub fn to_bytes(self) -> [u8; N] {
+        self.0
+    }
+
+    /// Returns a reference to the bytes of the key.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for OrderedKey<N> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+/// The byte array representing a value of the float type `F`.
+type Bytes<F> = <<F as RawFloat>::Bits as ToBytes>::Bytes;
+
+/// Conversions to and from the byte representation of the value.
+///
+/// These are available for every `RawFloat` whose bits can be converted to
+/// bytes, which includes all primitive unsigned integers.
+impl<F: RawFloat, C: FloatChecker<F>> NoisyFloat<F, C>
+where
+    F::Bits: ToBytes + FromBytes<Bytes = Bytes<F>>,
+{
+    /// Returns the memory representation of the value as a byte array
+    /// in little-endian byte order.
+    #[inline]
+    pub fn to_le_bytes(self) -> Bytes<F> {
+        ToBytes::to_le_bytes(&self.to_bits())
+    }
+
+    /// Returns the memory representation of the value as a byte array
+    /// in big-endian byte order.
+    #[inline]
+    pub fn to_be_bytes(self) -> Bytes<F> {
+        ToBytes::to_be_bytes(&self.to_bits())
+    }
+
+    /// Returns the memory representation of the value as a byte array
+    /// in native byte order.
+    #[inline]
+    pub fn to_ne_bytes(self) -> Bytes<F> {
+        ToBytes::to_ne_bytes(&self.to_bits())
+    }
+
+    /// Constructs a value from its representation as a byte array
+    /// in little-endian byte order.
+    ///
+    /// Returns an error if the value is rejected by the `FloatChecker`.
+    #[inline]
+    pub fn try_from_le_bytes(bytes: Bytes<F>) -> Result<Self, InvalidValueError> {
+        Self::try_from_bits(FromBytes::from_le_bytes(&bytes))
+    }
+
+    /// Constructs a value from its representation as a byte array
+    /// in big-endian byte order.
+    ///
+    /// Returns an error if the value is rejected by the `FloatChecker`.
+    #[inline]
+    pub fn try_from_be_bytes(bytes: Bytes<F>) -> Result<Self, InvalidValueError> {
+        Self::try_from_bits(FromBytes::from_be_bytes(&bytes))
+    }
+
+    /// Constructs a value from its representation as a byte array
+    /// in native byte order.
+    ///
+    /// Returns an error if the value is rejected by the `FloatChecker`.
+    #[inline]
+    pub fn try_from_ne_bytes(bytes: Bytes<F>) -> Result<Self, InvalidValueError> {
+        Self::try_from_bits(FromBytes::from_ne_bytes(&bytes))
+    }
+
+    /// Returns a byte representation whose lexicographic order matches
+    /// the order of the values, where `-0.0` and `+0.0` are encoded the same.
+    ///
+    /// See also `OrderedKey`.
+    #[inline]
+    pub fn to_ordered_bytes(self) -> Bytes<F> {
+        ToBytes::to_be_bytes(&self.ordered_bits())
+    }
+
+    /// Decodes a value encoded by `to_ordered_bytes`.
+    ///
+    /// Returns an error if the bytes are not a valid encoding, or if the
+    /// value is rejected by the `FloatChecker`.
+    #[inline]
+    pub fn from_ordered_bytes(bytes: Bytes<F>) -> Result<Self, InvalidValueError> {
+        Self::from_ordered_bits(FromBytes::from_be_bytes(&bytes))
+    }
+
+    /// Decodes a byte slice of consecutive values in little-endian byte order.
+    ///
+    /// Returns an error with the byte offset of the first value rejected
+    /// by the `FloatChecker`, or of the incomplete value at the end
+    /// if the length of the slice is not a multiple of the size of the value.
+    ///
+    /// Requires the `alloc` feature.
+    #[cfg(feature = "alloc")]
+    pub fn try_vec_from_le_bytes(bytes: &[u8]) -> Result<Vec<Self>, FromBytesError> {
+        Self::try_vec_from_bytes(bytes, Self::try_from_le_bytes)
+    }
+
+    /// Decodes a byte slice of consecutive values in big-endian byte order.
+    ///
+    /// Returns an error with the byte offset of the first value rejected
+    /// by the `FloatChecker`, or of the incomplete value at the end
+    /// if the length of the slice is not a multiple of the size of the value.
+    ///
+    /// Requires the `alloc` feature.
+    #[cfg(feature = "alloc")]
+    pub fn try_vec_from_be_bytes(bytes: &[u8]) -> Result<Vec<Self>, FromBytesError> {
+        Self::try_vec_from_bytes(bytes, Self::try_from_be_bytes)
+    }
+
+    /// Decodes a byte slice of consecutive values in native byte order.
+    ///
+    /// Returns an error with the byte offset of the first value rejected
+    /// by the `FloatChecker`, or of the incomplete value at the end
+    /// if the length of the slice is not a multiple of the size of the value.
+    ///
+    /// Requires the `alloc` feature.
+    #[cfg(feature = "alloc")]
+    pub fn try_vec_from_ne_bytes(bytes: &[u8]) -> Result<Vec<Self>, FromBytesError> {
+        Self::try_vec_from_bytes(bytes, Self::try_from_ne_bytes)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn try_vec_from_bytes(
+        bytes: &[u8],
+        decode: fn(Bytes<F>) -> Result<Self, InvalidValueError>,
+    ) -> Result<Vec<Self>, FromBytesError> {
+        let size = core::mem::size_of::<F::Bits>();
+        let chunks = bytes.chunks_exact(size);
+        let remainder = chunks.remainder().len();
+        let mut values = Vec::with_capacity(bytes.len() / size);
+        for (index, chunk) in chunks.enumerate() {
+            let mut array = ToBytes::to_ne_bytes(&<F::Bits as num_traits::Zero>::zero());
+            array.as_mut().copy_from_slice(chunk);
+            match decode(array) {
+                Ok(value) => values.push(value),
+                Err(InvalidValueError) => {
+                    return Err(FromBytesError::InvalidValue {
+                        offset: index * size,
+                    })
+                }
+            }
+        }
+        // invalid values are reported before an incomplete value at the end
+        if remainder != 0 {
+            return Err(FromBytesError::TrailingBytes {
+                offset: bytes.len() - remainder,
+            });
+        }
+        Ok(values)
+    }
+}
+
+impl<F: RawFloat, C: FloatChecker<F>, const N: usize> From<NoisyFloat<F, C>> for OrderedKey<N>
+where
+    F::Bits: ToBytes<Bytes = [u8; N]> + FromBytes<Bytes = [u8; N]>,
+{
+    #[inline]
+    fn from(n: NoisyFloat<F, C>) -> Self {
+        OrderedKey(n.to_ordered_bytes())
+    }
+}
+
+impl<F: RawFloat, C: FloatChecker<F>, const N: usize> TryFrom<OrderedKey<N>> for NoisyFloat<F, C>
+where
+    F::Bits: ToBytes<Bytes = [u8; N]> + FromBytes<Bytes = [u8; N]>,
+{
+    type Error = InvalidValueError;
+    #[inline]
+    fn try_from(key: OrderedKey<N>) -> Result<Self, Self::Error> {
+        Self::from_ordered_bytes(key.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn bytes() {
+        assert_eq!(r32(1.5).to_le_bytes(), 1.5f32.to_le_bytes());
+        assert_eq!(r64(1.5).to_be_bytes(), 1.5f64.to_be_bytes());
+        assert_eq!(n64(-2.0).to_ne_bytes(), (-2.0f64).to_ne_bytes());
+        assert_eq!(R32::try_from_le_bytes([0, 0, 192, 63]), Ok(r32(1.5)));
+        assert_eq!(R64::try_from_be_bytes(2.0f64.to_be_bytes()), Ok(r64(2.0)));
+        assert_eq!(
+            R64::try_from_ne_bytes(f64::INFINITY.to_ne_bytes()),
+            Err(InvalidValueError)
+        );
+        assert_eq!(
+            N32::try_from_le_bytes(f32::NAN.to_le_bytes()),
+            Err(InvalidValueError)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn bytes_bulk() {
+        use alloc::vec;
+
+        let mut bytes = Vec::new();
+        for &value in &[1.0f64, -2.5, 0.0] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        assert_eq!(
+            R64::try_vec_from_le_bytes(&bytes),
+            Ok(vec![r64(1.0), r64(-2.5), r64(0.0)])
+        );
+        assert_eq!(R64::try_vec_from_be_bytes(&[]), Ok(Vec::new()));
+
+        bytes.extend_from_slice(&f64::INFINITY.to_le_bytes());
+        assert_eq!(N64::try_vec_from_le_bytes(&bytes).map(|v| v.len()), Ok(4));
+        let error = R64::try_vec_from_le_bytes(&bytes).unwrap_err();
+        assert_eq!(error, FromBytesError::InvalidValue { offset: 24 });
+        assert_eq!(error.offset(), 24);
+
+        let bytes = [0, 0, 128, 63, 0, 0, 192, 127, 0];
+        assert_eq!(
+            R32::try_vec_from_le_bytes(&bytes),
+            Err(FromBytesError::InvalidValue { offset: 4 })
+        );
+        assert_eq!(
+            N32::try_vec_from_le_bytes(&[0, 0, 128, 127, 0]),
+            Err(FromBytesError::TrailingBytes { offset: 4 })
+        );
+        assert_eq!(
+            R32::try_vec_from_le_bytes(&bytes[..8]),
+            Err(FromBytesError::InvalidValue { offset: 4 })
+        );
+        assert_eq!(
+            R32::try_vec_from_le_bytes(&bytes[4..]),
+            Err(FromBytesError::InvalidValue { offset: 0 })
+        );
+        assert_eq!(
+            R32::try_vec_from_be_bytes(&1.25f32.to_be_bytes()),
+            Ok(vec![r32(1.25)])
+        );
+        assert_eq!(
+            R32::try_vec_from_ne_bytes(&1.25f32.to_ne_bytes()),
+            Ok(vec![r32(1.25)])
+        );
+    }
+
+    #[test]
+    fn ordered_bytes() {
+        assert_eq!(r64(-0.0).to_ordered_bytes(), r64(0.0).to_ordered_bytes());
+        assert_eq!(r32(0.0).to_ordered_bytes(), [0x80, 0, 0, 0]);
+        assert!(N64::NEG_INFINITY.to_ordered_bytes() < N64::MIN.to_ordered_bytes());
+        assert!(r64(-1.0).to_ordered_bytes() < r64(-0.5).to_ordered_bytes());
+        assert!(r64(0.5).to_ordered_bytes() < r64(1.0).to_ordered_bytes());
+        assert_eq!(
+            R64::from_ordered_bytes(r64(-2.5).to_ordered_bytes()),
+            Ok(r64(-2.5))
+        );
+        assert_eq!(R32::from_ordered_bytes([0; 4]), Err(InvalidValueError));
+        assert_eq!(
+            R32::from_ordered_bytes(N32::INFINITY.to_ordered_bytes()),
+            Err(InvalidValueError)
+        );
+        assert_eq!(
+            N32::from_ordered_bytes(N32::INFINITY.to_ordered_bytes()),
+            Ok(N32::INFINITY)
+        );
+
+        let key = OrderedKey::from(r32(3.0));
+        assert_eq!(key.as_ref(), &r32(3.0).to_ordered_bytes()[..]);
+        assert_eq!(
+            R32::try_from(OrderedKey::from_bytes(key.to_bytes())),
+            Ok(r32(3.0))
+        );
+        assert_eq!(
+            R64::try_from(OrderedKey::from_bytes([0; 8])),
+            Err(InvalidValueError)
+        );
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn ordered_bytes_preserve_order(a in proptest::num::u64::ANY, b in proptest::num::u64::ANY) {
+            let (a, b) = (f64::from_bits(a), f64::from_bits(b));
+            proptest::prop_assume!(!a.is_nan() && !b.is_nan());
+            let (a, b) = (n64(a), n64(b));
+            proptest::prop_assert_eq!(
+                a.cmp(&b),
+                a.to_ordered_bytes().cmp(&b.to_ordered_bytes())
+            );
+            proptest::prop_assert_eq!(N64::from_ordered_bytes(a.to_ordered_bytes()), Ok(a));
+        }
+
+        #[test]
+        fn ordered_keys_preserve_order(a in proptest::num::f32::ANY, b in proptest::num::f32::ANY) {
+            proptest::prop_assume!(a.is_finite() && b.is_finite());
+            let (a, b) = (r32(a), r32(b));
+            proptest::prop_assert_eq!(
+                a.cmp(&b),
+                OrderedKey::from(a).cmp(&OrderedKey::from(b))
+            );
+        }
+    }
+}
@@ -15,7 +15,7 @@
 //! Standard implementations of `FloatChecker`.
 
 use crate::{FloatChecker, NoisyFloat};
-use num_traits::Float;
+use num_traits::float::FloatCore;
 
 /// A `FloatChecker` that considers all values valid except NaN.
 ///
@@ -24,7 +24,7 @@ use num_traits::Float;
 /// The `assert` method is implemented using `debug_assert!`.
 pub struct NumChecker;
 
-impl<F: Float> FloatChecker<F> for NumChecker {
+impl<F: FloatCore> FloatChecker<F> for NumChecker {
     #[inline]
     fn assert(value: F) {
         debug_assert!(Self::check(value), "unexpected NaN");
@@ -41,7 +41,7 @@ impl<F: Float> FloatChecker<F> for NumChecker {
 /// The `assert` method is implemented using `debug_assert!`.
 pub struct FiniteChecker;
 
-impl<F: Float> FloatChecker<F> for FiniteChecker {
+impl<F: FloatCore> FloatChecker<F> for FiniteChecker {
     #[inline]
     fn assert(value: F) {
         debug_assert!(Self::check(value), "unexpected NaN or infinity");
@@ -53,8 +53,8 @@ impl<F: Float> FloatChecker<F> for FiniteChecker {
     }
 }
 
-impl<F: Float> From<NoisyFloat<F, FiniteChecker>> for NoisyFloat<F, NumChecker> {
+impl<F: FloatCore> From<NoisyFloat<F, FiniteChecker>> for NoisyFloat<F, NumChecker> {
     fn from(value: NoisyFloat<F, FiniteChecker>) -> Self {
-        Self::unchecked_new(value.raw())
+        Self::new_unchecked(value.raw())
     }
 }
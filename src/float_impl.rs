@@ -12,80 +12,81 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{FloatChecker, NoisyFloat};
+use crate::{FloatChecker, NoisyFloat, RawFloat};
 use core::{
     cmp::Ordering,
-    convert::{From, TryFrom},
     hash::{Hash, Hasher},
     iter,
-    mem::transmute,
     num::FpCategory,
     ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign},
 };
+#[cfg(any(feature = "std", feature = "libm"))]
+use num_traits::Float;
 use num_traits::{
     cast::{FromPrimitive, NumCast, ToPrimitive},
+    float::FloatCore,
     identities::{One, Zero},
-    Bounded, Float, FloatConst, Num, Signed,
+    Bounded, FloatConst, Num, Signed,
 };
 
-impl<F: Float, C: FloatChecker<F>> Clone for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Clone for NoisyFloat<F, C> {
     #[inline]
     fn clone(&self) -> Self {
-        Self::unchecked_new(self.value)
+        *self
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Copy for NoisyFloat<F, C> {}
+impl<F: FloatCore, C: FloatChecker<F>> Copy for NoisyFloat<F, C> {}
 
-impl<F: Float, C: FloatChecker<F>> AsRef<F> for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> AsRef<F> for NoisyFloat<F, C> {
     fn as_ref(&self) -> &F {
         &self.value
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> PartialEq<F> for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> PartialEq<F> for NoisyFloat<F, C> {
     #[inline]
     fn eq(&self, other: &F) -> bool {
-        self.value.eq(&other)
+        self.value.eq(other)
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> PartialEq for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> PartialEq for NoisyFloat<F, C> {
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         self.eq(&other.value)
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Eq for NoisyFloat<F, C> {}
+impl<F: FloatCore, C: FloatChecker<F>> Eq for NoisyFloat<F, C> {}
 
-impl<F: Float, C: FloatChecker<F>> PartialOrd<F> for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> PartialOrd<F> for NoisyFloat<F, C> {
     #[inline]
     fn partial_cmp(&self, other: &F) -> Option<Ordering> {
-        self.value.partial_cmp(&other)
+        self.value.partial_cmp(other)
     }
     #[inline]
     fn lt(&self, other: &F) -> bool {
-        self.value.lt(&other)
+        self.value.lt(other)
     }
     #[inline]
     fn le(&self, other: &F) -> bool {
-        self.value.le(&other)
+        self.value.le(other)
     }
     #[inline]
     fn gt(&self, other: &F) -> bool {
-        self.value.gt(&other)
+        self.value.gt(other)
     }
     #[inline]
     fn ge(&self, other: &F) -> bool {
-        self.value.ge(&other)
+        self.value.ge(other)
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> PartialOrd for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> PartialOrd for NoisyFloat<F, C> {
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.value.partial_cmp(&other.value)
+        Some(self.cmp(other))
     }
     #[inline]
     fn lt(&self, other: &Self) -> bool {
@@ -105,46 +106,90 @@ impl<F: Float, C: FloatChecker<F>> PartialOrd for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Ord for NoisyFloat<F, C> {
+// These are written as selects on a single float comparison, rather than as
+// branches on `cmp`, so that they compile to branchless code such as
+// `minsd`/`maxsd` on x86. Hinting that NaN is impossible (e.g. with
+// `unreachable_unchecked`) would be unsound, since the standard checkers
+// only use `debug_assert!`, so NaN can be stored in a release build.
+impl<F: FloatCore, C: FloatChecker<F>> Ord for NoisyFloat<F, C> {
     #[inline]
     fn cmp(&self, other: &Self) -> Ordering {
-        if self.value < other.value {
-            Ordering::Less
-        } else if self.value == other.value {
-            Ordering::Equal
+        let greater = (self.value > other.value) as i8;
+        let less = (self.value < other.value) as i8;
+        match greater - less {
+            -1 => Ordering::Less,
+            0 => Ordering::Equal,
+            _ => Ordering::Greater,
+        }
+    }
+
+    #[inline]
+    fn max(self, other: Self) -> Self {
+        if self.value > other.value {
+            self
         } else {
-            Ordering::Greater
+            other
         }
     }
-}
 
-impl<C: FloatChecker<f32>> Hash for NoisyFloat<f32, C> {
     #[inline]
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        let bits = if self.value == 0.0 {
-            0 // this accounts for +0.0 and -0.0
+    fn min(self, other: Self) -> Self {
+        if other.value < self.value {
+            other
         } else {
-            unsafe { transmute::<f32, u32>(self.value) }
-        };
-        bits.hash(state);
+            self
+        }
+    }
+
+    #[inline]
+    fn clamp(self, min: Self, max: Self) -> Self {
+        assert!(min.value <= max.value);
+        let value = if min.value > self.value { min } else { self };
+        if value.value > max.value {
+            max
+        } else {
+            value
+        }
+    }
+}
+
+impl RawFloat for f32 {
+    type Bits = u32;
+    #[inline]
+    fn to_bits(self) -> u32 {
+        f32::to_bits(self)
+    }
+    #[inline]
+    fn from_bits(bits: u32) -> Self {
+        f32::from_bits(bits)
+    }
+}
+
+impl RawFloat for f64 {
+    type Bits = u64;
+    #[inline]
+    fn to_bits(self) -> u64 {
+        f64::to_bits(self)
+    }
+    #[inline]
+    fn from_bits(bits: u64) -> Self {
+        f64::from_bits(bits)
     }
 }
 
-impl<C: FloatChecker<f64>> Hash for NoisyFloat<f64, C> {
+impl<F: RawFloat, C: FloatChecker<F>> Hash for NoisyFloat<F, C> {
     #[inline]
     fn hash<H: Hasher>(&self, state: &mut H) {
-        let bits = if self.value == 0.0 {
-            0 // this accounts for +0.0 and -0.0
+        let bits = if self.value.is_zero() {
+            F::zero().to_bits() // this accounts for +0.0 and -0.0
         } else {
-            unsafe { transmute::<f64, u64>(self.value) }
+            self.value.to_bits()
         };
         bits.hash(state);
     }
 }
 
-// TODO why is `impl<F: Float + Hash, C: FloatChecker<F>> Hash for NoisyFloat<F, C>` considered conflicting?
-
-impl<F: Float, C: FloatChecker<F>> Add<F> for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Add<F> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn add(self, rhs: F) -> Self {
@@ -152,7 +197,7 @@ impl<F: Float, C: FloatChecker<F>> Add<F> for NoisyFloat<F, C> {
     }
 }
 
-impl<'a, F: Float, C: FloatChecker<F>> Add<&'a F> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore, C: FloatChecker<F>> Add<&'a F> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn add(self, rhs: &'a F) -> Self {
@@ -160,7 +205,7 @@ impl<'a, F: Float, C: FloatChecker<F>> Add<&'a F> for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Add for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Add for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn add(self, rhs: Self) -> Self {
@@ -168,7 +213,7 @@ impl<F: Float, C: FloatChecker<F>> Add for NoisyFloat<F, C> {
     }
 }
 
-impl<'a, F: Float, C: FloatChecker<F>> Add<&'a Self> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore, C: FloatChecker<F>> Add<&'a Self> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn add(self, rhs: &'a Self) -> Self {
@@ -176,7 +221,7 @@ impl<'a, F: Float, C: FloatChecker<F>> Add<&'a Self> for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Sub<F> for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Sub<F> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn sub(self, rhs: F) -> Self {
@@ -184,7 +229,7 @@ impl<F: Float, C: FloatChecker<F>> Sub<F> for NoisyFloat<F, C> {
     }
 }
 
-impl<'a, F: Float, C: FloatChecker<F>> Sub<&'a F> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore, C: FloatChecker<F>> Sub<&'a F> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn sub(self, rhs: &'a F) -> Self {
@@ -192,7 +237,7 @@ impl<'a, F: Float, C: FloatChecker<F>> Sub<&'a F> for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Sub for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Sub for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn sub(self, rhs: Self) -> Self {
@@ -200,7 +245,7 @@ impl<F: Float, C: FloatChecker<F>> Sub for NoisyFloat<F, C> {
     }
 }
 
-impl<'a, F: Float, C: FloatChecker<F>> Sub<&'a Self> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore, C: FloatChecker<F>> Sub<&'a Self> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn sub(self, rhs: &'a Self) -> Self {
@@ -208,7 +253,7 @@ impl<'a, F: Float, C: FloatChecker<F>> Sub<&'a Self> for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Mul<F> for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Mul<F> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn mul(self, rhs: F) -> Self {
@@ -216,7 +261,7 @@ impl<F: Float, C: FloatChecker<F>> Mul<F> for NoisyFloat<F, C> {
     }
 }
 
-impl<'a, F: Float, C: FloatChecker<F>> Mul<&'a F> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore, C: FloatChecker<F>> Mul<&'a F> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn mul(self, rhs: &'a F) -> Self {
@@ -224,7 +269,7 @@ impl<'a, F: Float, C: FloatChecker<F>> Mul<&'a F> for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Mul for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Mul for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn mul(self, rhs: Self) -> Self {
@@ -232,7 +277,7 @@ impl<F: Float, C: FloatChecker<F>> Mul for NoisyFloat<F, C> {
     }
 }
 
-impl<'a, F: Float, C: FloatChecker<F>> Mul<&'a Self> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore, C: FloatChecker<F>> Mul<&'a Self> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn mul(self, rhs: &'a Self) -> Self {
@@ -240,7 +285,7 @@ impl<'a, F: Float, C: FloatChecker<F>> Mul<&'a Self> for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Div<F> for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Div<F> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn div(self, rhs: F) -> Self {
@@ -248,7 +293,7 @@ impl<F: Float, C: FloatChecker<F>> Div<F> for NoisyFloat<F, C> {
     }
 }
 
-impl<'a, F: Float, C: FloatChecker<F>> Div<&'a F> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore, C: FloatChecker<F>> Div<&'a F> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn div(self, rhs: &'a F) -> Self {
@@ -256,7 +301,7 @@ impl<'a, F: Float, C: FloatChecker<F>> Div<&'a F> for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Div for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Div for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn div(self, rhs: Self) -> Self {
@@ -264,7 +309,7 @@ impl<F: Float, C: FloatChecker<F>> Div for NoisyFloat<F, C> {
     }
 }
 
-impl<'a, F: Float, C: FloatChecker<F>> Div<&'a Self> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore, C: FloatChecker<F>> Div<&'a Self> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn div(self, rhs: &'a Self) -> Self {
@@ -272,7 +317,7 @@ impl<'a, F: Float, C: FloatChecker<F>> Div<&'a Self> for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Rem<F> for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Rem<F> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn rem(self, rhs: F) -> Self {
@@ -280,7 +325,7 @@ impl<F: Float, C: FloatChecker<F>> Rem<F> for NoisyFloat<F, C> {
     }
 }
 
-impl<'a, F: Float, C: FloatChecker<F>> Rem<&'a F> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore, C: FloatChecker<F>> Rem<&'a F> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn rem(self, rhs: &'a F) -> Self {
@@ -288,7 +333,7 @@ impl<'a, F: Float, C: FloatChecker<F>> Rem<&'a F> for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Rem for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Rem for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn rem(self, rhs: Self) -> Self {
@@ -296,7 +341,7 @@ impl<F: Float, C: FloatChecker<F>> Rem for NoisyFloat<F, C> {
     }
 }
 
-impl<'a, F: Float, C: FloatChecker<F>> Rem<&'a Self> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore, C: FloatChecker<F>> Rem<&'a Self> for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn rem(self, rhs: &'a Self) -> Self {
@@ -304,7 +349,7 @@ impl<'a, F: Float, C: FloatChecker<F>> Rem<&'a Self> for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float + AddAssign, C: FloatChecker<F>> AddAssign<F> for NoisyFloat<F, C> {
+impl<F: FloatCore + AddAssign, C: FloatChecker<F>> AddAssign<F> for NoisyFloat<F, C> {
     #[inline]
     fn add_assign(&mut self, rhs: F) {
         self.value.add_assign(rhs);
@@ -312,7 +357,7 @@ impl<F: Float + AddAssign, C: FloatChecker<F>> AddAssign<F> for NoisyFloat<F, C>
     }
 }
 
-impl<'a, F: Float + AddAssign, C: FloatChecker<F>> AddAssign<&'a F> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore + AddAssign, C: FloatChecker<F>> AddAssign<&'a F> for NoisyFloat<F, C> {
     #[inline]
     fn add_assign(&mut self, rhs: &'a F) {
         self.value.add_assign(*rhs);
@@ -320,21 +365,21 @@ impl<'a, F: Float + AddAssign, C: FloatChecker<F>> AddAssign<&'a F> for NoisyFlo
     }
 }
 
-impl<F: Float + AddAssign, C: FloatChecker<F>> AddAssign for NoisyFloat<F, C> {
+impl<F: FloatCore + AddAssign, C: FloatChecker<F>> AddAssign for NoisyFloat<F, C> {
     #[inline]
     fn add_assign(&mut self, rhs: Self) {
         self.add_assign(rhs.value);
     }
 }
 
-impl<'a, F: Float + AddAssign, C: FloatChecker<F>> AddAssign<&'a Self> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore + AddAssign, C: FloatChecker<F>> AddAssign<&'a Self> for NoisyFloat<F, C> {
     #[inline]
     fn add_assign(&mut self, rhs: &'a Self) {
         self.add_assign(rhs.value);
     }
 }
 
-impl<F: Float + SubAssign, C: FloatChecker<F>> SubAssign<F> for NoisyFloat<F, C> {
+impl<F: FloatCore + SubAssign, C: FloatChecker<F>> SubAssign<F> for NoisyFloat<F, C> {
     #[inline]
     fn sub_assign(&mut self, rhs: F) {
         self.value.sub_assign(rhs);
@@ -342,7 +387,7 @@ impl<F: Float + SubAssign, C: FloatChecker<F>> SubAssign<F> for NoisyFloat<F, C>
     }
 }
 
-impl<'a, F: Float + SubAssign, C: FloatChecker<F>> SubAssign<&'a F> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore + SubAssign, C: FloatChecker<F>> SubAssign<&'a F> for NoisyFloat<F, C> {
     #[inline]
     fn sub_assign(&mut self, rhs: &'a F) {
         self.value.sub_assign(*rhs);
@@ -350,21 +395,21 @@ impl<'a, F: Float + SubAssign, C: FloatChecker<F>> SubAssign<&'a F> for NoisyFlo
     }
 }
 
-impl<F: Float + SubAssign, C: FloatChecker<F>> SubAssign for NoisyFloat<F, C> {
+impl<F: FloatCore + SubAssign, C: FloatChecker<F>> SubAssign for NoisyFloat<F, C> {
     #[inline]
     fn sub_assign(&mut self, rhs: Self) {
         self.sub_assign(rhs.value);
     }
 }
 
-impl<'a, F: Float + SubAssign, C: FloatChecker<F>> SubAssign<&'a Self> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore + SubAssign, C: FloatChecker<F>> SubAssign<&'a Self> for NoisyFloat<F, C> {
     #[inline]
     fn sub_assign(&mut self, rhs: &'a Self) {
         self.sub_assign(rhs.value);
     }
 }
 
-impl<F: Float + MulAssign, C: FloatChecker<F>> MulAssign<F> for NoisyFloat<F, C> {
+impl<F: FloatCore + MulAssign, C: FloatChecker<F>> MulAssign<F> for NoisyFloat<F, C> {
     #[inline]
     fn mul_assign(&mut self, rhs: F) {
         self.value.mul_assign(rhs);
@@ -372,7 +417,7 @@ impl<F: Float + MulAssign, C: FloatChecker<F>> MulAssign<F> for NoisyFloat<F, C>
     }
 }
 
-impl<'a, F: Float + MulAssign, C: FloatChecker<F>> MulAssign<&'a F> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore + MulAssign, C: FloatChecker<F>> MulAssign<&'a F> for NoisyFloat<F, C> {
     #[inline]
     fn mul_assign(&mut self, rhs: &'a F) {
         self.value.mul_assign(*rhs);
@@ -380,21 +425,21 @@ impl<'a, F: Float + MulAssign, C: FloatChecker<F>> MulAssign<&'a F> for NoisyFlo
     }
 }
 
-impl<F: Float + MulAssign, C: FloatChecker<F>> MulAssign for NoisyFloat<F, C> {
+impl<F: FloatCore + MulAssign, C: FloatChecker<F>> MulAssign for NoisyFloat<F, C> {
     #[inline]
     fn mul_assign(&mut self, rhs: Self) {
         self.mul_assign(rhs.value);
     }
 }
 
-impl<'a, F: Float + MulAssign, C: FloatChecker<F>> MulAssign<&'a Self> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore + MulAssign, C: FloatChecker<F>> MulAssign<&'a Self> for NoisyFloat<F, C> {
     #[inline]
     fn mul_assign(&mut self, rhs: &'a Self) {
         self.mul_assign(rhs.value);
     }
 }
 
-impl<F: Float + DivAssign, C: FloatChecker<F>> DivAssign<F> for NoisyFloat<F, C> {
+impl<F: FloatCore + DivAssign, C: FloatChecker<F>> DivAssign<F> for NoisyFloat<F, C> {
     #[inline]
     fn div_assign(&mut self, rhs: F) {
         self.value.div_assign(rhs);
@@ -402,7 +447,7 @@ impl<F: Float + DivAssign, C: FloatChecker<F>> DivAssign<F> for NoisyFloat<F, C>
     }
 }
 
-impl<'a, F: Float + DivAssign, C: FloatChecker<F>> DivAssign<&'a F> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore + DivAssign, C: FloatChecker<F>> DivAssign<&'a F> for NoisyFloat<F, C> {
     #[inline]
     fn div_assign(&mut self, rhs: &'a F) {
         self.value.div_assign(*rhs);
@@ -410,21 +455,21 @@ impl<'a, F: Float + DivAssign, C: FloatChecker<F>> DivAssign<&'a F> for NoisyFlo
     }
 }
 
-impl<F: Float + DivAssign, C: FloatChecker<F>> DivAssign for NoisyFloat<F, C> {
+impl<F: FloatCore + DivAssign, C: FloatChecker<F>> DivAssign for NoisyFloat<F, C> {
     #[inline]
     fn div_assign(&mut self, rhs: Self) {
         self.div_assign(rhs.value);
     }
 }
 
-impl<'a, F: Float + DivAssign, C: FloatChecker<F>> DivAssign<&'a Self> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore + DivAssign, C: FloatChecker<F>> DivAssign<&'a Self> for NoisyFloat<F, C> {
     #[inline]
     fn div_assign(&mut self, rhs: &'a Self) {
         self.div_assign(rhs.value);
     }
 }
 
-impl<F: Float + RemAssign, C: FloatChecker<F>> RemAssign<F> for NoisyFloat<F, C> {
+impl<F: FloatCore + RemAssign, C: FloatChecker<F>> RemAssign<F> for NoisyFloat<F, C> {
     #[inline]
     fn rem_assign(&mut self, rhs: F) {
         self.value.rem_assign(rhs);
@@ -432,7 +477,7 @@ impl<F: Float + RemAssign, C: FloatChecker<F>> RemAssign<F> for NoisyFloat<F, C>
     }
 }
 
-impl<'a, F: Float + RemAssign, C: FloatChecker<F>> RemAssign<&'a F> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore + RemAssign, C: FloatChecker<F>> RemAssign<&'a F> for NoisyFloat<F, C> {
     #[inline]
     fn rem_assign(&mut self, rhs: &'a F) {
         self.value.rem_assign(*rhs);
@@ -440,21 +485,21 @@ impl<'a, F: Float + RemAssign, C: FloatChecker<F>> RemAssign<&'a F> for NoisyFlo
     }
 }
 
-impl<F: Float + RemAssign, C: FloatChecker<F>> RemAssign for NoisyFloat<F, C> {
+impl<F: FloatCore + RemAssign, C: FloatChecker<F>> RemAssign for NoisyFloat<F, C> {
     #[inline]
     fn rem_assign(&mut self, rhs: Self) {
         self.rem_assign(rhs.value);
     }
 }
 
-impl<'a, F: Float + RemAssign, C: FloatChecker<F>> RemAssign<&'a Self> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore + RemAssign, C: FloatChecker<F>> RemAssign<&'a Self> for NoisyFloat<F, C> {
     #[inline]
     fn rem_assign(&mut self, rhs: &'a Self) {
         self.rem_assign(rhs.value);
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Neg for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Neg for NoisyFloat<F, C> {
     type Output = Self;
     #[inline]
     fn neg(self) -> Self {
@@ -462,7 +507,7 @@ impl<F: Float, C: FloatChecker<F>> Neg for NoisyFloat<F, C> {
     }
 }
 
-impl<'a, F: Float, C: FloatChecker<F>> Neg for &'a NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Neg for &NoisyFloat<F, C> {
     type Output = NoisyFloat<F, C>;
     #[inline]
     fn neg(self) -> Self::Output {
@@ -470,7 +515,7 @@ impl<'a, F: Float, C: FloatChecker<F>> Neg for &'a NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Zero for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Zero for NoisyFloat<F, C> {
     #[inline]
     fn zero() -> Self {
         Self::new(F::zero())
@@ -481,14 +526,14 @@ impl<F: Float, C: FloatChecker<F>> Zero for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> One for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> One for NoisyFloat<F, C> {
     #[inline]
     fn one() -> Self {
         Self::new(F::one())
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> Num for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> Num for NoisyFloat<F, C> {
     type FromStrRadixErr = F::FromStrRadixErr;
     #[inline]
     fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
@@ -496,7 +541,7 @@ impl<F: Float, C: FloatChecker<F>> Num for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> ToPrimitive for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> ToPrimitive for NoisyFloat<F, C> {
     #[inline]
     fn to_i64(&self) -> Option<i64> {
         self.value.to_i64()
@@ -547,7 +592,7 @@ impl<F: Float, C: FloatChecker<F>> ToPrimitive for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float + FromPrimitive, C: FloatChecker<F>> FromPrimitive for NoisyFloat<F, C> {
+impl<F: FloatCore + FromPrimitive, C: FloatChecker<F>> FromPrimitive for NoisyFloat<F, C> {
     #[inline]
     fn from_isize(n: isize) -> Option<Self> {
         Self::try_new(F::from_isize(n)?)
@@ -606,27 +651,50 @@ impl<F: Float + FromPrimitive, C: FloatChecker<F>> FromPrimitive for NoisyFloat<
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> NumCast for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> NumCast for NoisyFloat<F, C> {
     #[inline]
     fn from<T: ToPrimitive>(n: T) -> Option<Self> {
         F::from(n).and_then(|v| Self::try_new(v))
     }
 }
 
-impl<C: FloatChecker<f32>> From<NoisyFloat<f32, C>> for f32 {
-    #[inline]
-    fn from(n: NoisyFloat<f32, C>) -> Self {
-        n.value
-    }
-}
+/// Implements the conversions between a float type and `NoisyFloat` that
+/// cannot be written generically.
+///
+/// Coherence rules forbid a blanket `impl<F> From<NoisyFloat<F, C>> for F`
+/// (and `TryFrom<F>` conflicts with the blanket `TryFrom` impl in `core`),
+/// so these impls are generated per float type.
+/// Invoking `impl_float_conversions!(MyFloat)` in the crate that defines
+/// `MyFloat` implements `From<NoisyFloat<MyFloat, C>> for MyFloat` and
+/// `TryFrom<MyFloat> for NoisyFloat<MyFloat, C>` for every checker `C`,
+/// failing with `InvalidValueError`.
+#[macro_export]
+macro_rules! impl_float_conversions {
+    ($float:ty) => {
+        impl<C: $crate::FloatChecker<$float>> ::core::convert::From<$crate::NoisyFloat<$float, C>>
+            for $float
+        {
+            #[inline]
+            fn from(n: $crate::NoisyFloat<$float, C>) -> Self {
+                n.raw()
+            }
+        }
 
-impl<C: FloatChecker<f64>> From<NoisyFloat<f64, C>> for f64 {
-    #[inline]
-    fn from(n: NoisyFloat<f64, C>) -> Self {
-        n.value
-    }
+        impl<C: $crate::FloatChecker<$float>> ::core::convert::TryFrom<$float>
+            for $crate::NoisyFloat<$float, C>
+        {
+            type Error = $crate::InvalidValueError;
+            #[inline]
+            fn try_from(f: $float) -> ::core::result::Result<Self, Self::Error> {
+                Self::try_new(f).ok_or($crate::InvalidValueError)
+            }
+        }
+    };
 }
 
+crate::impl_float_conversions!(f32);
+crate::impl_float_conversions!(f64);
+
 impl<C: FloatChecker<f32>> From<NoisyFloat<f32, C>> for f64 {
     #[inline]
     fn from(n: NoisyFloat<f32, C>) -> Self {
@@ -634,23 +702,7 @@ impl<C: FloatChecker<f32>> From<NoisyFloat<f32, C>> for f64 {
     }
 }
 
-impl<C: FloatChecker<f64>> TryFrom<f64> for NoisyFloat<f64, C> {
-    type Error = &'static str;
-    #[inline]
-    fn try_from(f: f64) -> Result<Self, Self::Error> {
-        Self::try_new(f).ok_or("illegal value")
-    }
-}
-
-impl<C: FloatChecker<f32>> TryFrom<f32> for NoisyFloat<f32, C> {
-    type Error = &'static str;
-    #[inline]
-    fn try_from(f: f32) -> Result<Self, Self::Error> {
-        Self::try_new(f).ok_or("illegal value")
-    }
-}
-
-impl<F: Float, C: FloatChecker<F>> Float for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> FloatCore for NoisyFloat<F, C> {
     #[inline]
     fn nan() -> Self {
         panic!("unexpected NaN")
@@ -676,6 +728,10 @@ impl<F: Float, C: FloatChecker<F>> Float for NoisyFloat<F, C> {
         Self::new(F::min_positive_value())
     }
     #[inline]
+    fn epsilon() -> Self {
+        Self::new(F::epsilon())
+    }
+    #[inline]
     fn max_value() -> Self {
         Self::new(F::max_value())
     }
@@ -736,8 +792,12 @@ impl<F: Float, C: FloatChecker<F>> Float for NoisyFloat<F, C> {
         self.value.is_sign_negative()
     }
     #[inline]
-    fn mul_add(self, a: Self, b: Self) -> Self {
-        Self::new(self.value.mul_add(a.value, b.value))
+    fn min(self, other: Self) -> Self {
+        Self::new(self.value.min(other.value))
+    }
+    #[inline]
+    fn max(self, other: Self) -> Self {
+        Self::new(self.value.max(other.value))
     }
     #[inline]
     fn recip(self) -> Self {
@@ -748,141 +808,253 @@ impl<F: Float, C: FloatChecker<F>> Float for NoisyFloat<F, C> {
         Self::new(self.value.powi(n))
     }
     #[inline]
+    fn to_degrees(self) -> Self {
+        Self::new(self.value.to_degrees())
+    }
+    #[inline]
+    fn to_radians(self) -> Self {
+        Self::new(self.value.to_radians())
+    }
+    #[inline]
+    fn integer_decode(self) -> (u64, i16, i8) {
+        self.value.integer_decode()
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<F: Float + FloatCore, C: FloatChecker<F>> Float for NoisyFloat<F, C> {
+    #[inline]
+    fn nan() -> Self {
+        panic!("unexpected NaN")
+    }
+    #[inline]
+    fn infinity() -> Self {
+        Self::new(<F as Float>::infinity())
+    }
+    #[inline]
+    fn neg_infinity() -> Self {
+        Self::new(<F as Float>::neg_infinity())
+    }
+    #[inline]
+    fn neg_zero() -> Self {
+        Self::new(<F as Float>::neg_zero())
+    }
+    #[inline]
+    fn min_value() -> Self {
+        Self::new(<F as Float>::min_value())
+    }
+    #[inline]
+    fn min_positive_value() -> Self {
+        Self::new(<F as Float>::min_positive_value())
+    }
+    #[inline]
+    fn max_value() -> Self {
+        Self::new(<F as Float>::max_value())
+    }
+    #[inline]
+    fn is_nan(self) -> bool {
+        Float::is_nan(self.value)
+    }
+    #[inline]
+    fn is_infinite(self) -> bool {
+        Float::is_infinite(self.value)
+    }
+    #[inline]
+    fn is_finite(self) -> bool {
+        Float::is_finite(self.value)
+    }
+    #[inline]
+    fn is_normal(self) -> bool {
+        Float::is_normal(self.value)
+    }
+    #[inline]
+    fn classify(self) -> FpCategory {
+        Float::classify(self.value)
+    }
+    #[inline]
+    fn floor(self) -> Self {
+        Self::new(Float::floor(self.value))
+    }
+    #[inline]
+    fn ceil(self) -> Self {
+        Self::new(Float::ceil(self.value))
+    }
+    #[inline]
+    fn round(self) -> Self {
+        Self::new(Float::round(self.value))
+    }
+    #[inline]
+    fn trunc(self) -> Self {
+        Self::new(Float::trunc(self.value))
+    }
+    #[inline]
+    fn fract(self) -> Self {
+        Self::new(Float::fract(self.value))
+    }
+    #[inline]
+    fn abs(self) -> Self {
+        Self::new(Float::abs(self.value))
+    }
+    #[inline]
+    fn signum(self) -> Self {
+        Self::new(Float::signum(self.value))
+    }
+    #[inline]
+    fn is_sign_positive(self) -> bool {
+        Float::is_sign_positive(self.value)
+    }
+    #[inline]
+    fn is_sign_negative(self) -> bool {
+        Float::is_sign_negative(self.value)
+    }
+    #[inline]
+    fn mul_add(self, a: Self, b: Self) -> Self {
+        Self::new(Float::mul_add(self.value, a.value, b.value))
+    }
+    #[inline]
+    fn recip(self) -> Self {
+        Self::new(Float::recip(self.value))
+    }
+    #[inline]
+    fn powi(self, n: i32) -> Self {
+        Self::new(Float::powi(self.value, n))
+    }
+    #[inline]
     fn powf(self, n: Self) -> Self {
-        Self::new(self.value.powf(n.value))
+        Self::new(Float::powf(self.value, n.value))
     }
     #[inline]
     fn sqrt(self) -> Self {
-        Self::new(self.value.sqrt())
+        Self::new(Float::sqrt(self.value))
     }
     #[inline]
     fn exp(self) -> Self {
-        Self::new(self.value.exp())
+        Self::new(Float::exp(self.value))
     }
     #[inline]
     fn exp2(self) -> Self {
-        Self::new(self.value.exp2())
+        Self::new(Float::exp2(self.value))
     }
     #[inline]
     fn ln(self) -> Self {
-        Self::new(self.value.ln())
+        Self::new(Float::ln(self.value))
     }
     #[inline]
     fn log(self, base: Self) -> Self {
-        Self::new(self.value.log(base.value))
+        Self::new(Float::log(self.value, base.value))
     }
     #[inline]
     fn log2(self) -> Self {
-        Self::new(self.value.log2())
+        Self::new(Float::log2(self.value))
     }
     #[inline]
     fn log10(self) -> Self {
-        Self::new(self.value.log10())
+        Self::new(Float::log10(self.value))
     }
     #[inline]
     fn max(self, other: Self) -> Self {
-        Self::new(self.value.max(other.value))
+        Self::new(Float::max(self.value, other.value))
     }
     #[inline]
     fn min(self, other: Self) -> Self {
-        Self::new(self.value.min(other.value))
+        Self::new(Float::min(self.value, other.value))
     }
     #[inline]
     fn abs_sub(self, other: Self) -> Self {
-        Self::new(self.value.abs_sub(other.value))
+        Self::new(Float::abs_sub(self.value, other.value))
     }
     #[inline]
     fn cbrt(self) -> Self {
-        Self::new(self.value.cbrt())
+        Self::new(Float::cbrt(self.value))
     }
     #[inline]
     fn hypot(self, other: Self) -> Self {
-        Self::new(self.value.hypot(other.value))
+        Self::new(Float::hypot(self.value, other.value))
     }
     #[inline]
     fn sin(self) -> Self {
-        Self::new(self.value.sin())
+        Self::new(Float::sin(self.value))
     }
     #[inline]
     fn cos(self) -> Self {
-        Self::new(self.value.cos())
+        Self::new(Float::cos(self.value))
     }
     #[inline]
     fn tan(self) -> Self {
-        Self::new(self.value.tan())
+        Self::new(Float::tan(self.value))
     }
     #[inline]
     fn asin(self) -> Self {
-        Self::new(self.value.asin())
+        Self::new(Float::asin(self.value))
     }
     #[inline]
     fn acos(self) -> Self {
-        Self::new(self.value.acos())
+        Self::new(Float::acos(self.value))
     }
     #[inline]
     fn atan(self) -> Self {
-        Self::new(self.value.atan())
+        Self::new(Float::atan(self.value))
     }
     #[inline]
     fn atan2(self, other: Self) -> Self {
-        Self::new(self.value.atan2(other.value))
+        Self::new(Float::atan2(self.value, other.value))
     }
     #[inline]
     fn sin_cos(self) -> (Self, Self) {
-        let (a, b) = self.value.sin_cos();
+        let (a, b) = Float::sin_cos(self.value);
         (Self::new(a), Self::new(b))
     }
     #[inline]
     fn exp_m1(self) -> Self {
-        Self::new(self.value.exp_m1())
+        Self::new(Float::exp_m1(self.value))
     }
     #[inline]
     fn ln_1p(self) -> Self {
-        Self::new(self.value.ln_1p())
+        Self::new(Float::ln_1p(self.value))
     }
     #[inline]
     fn sinh(self) -> Self {
-        Self::new(self.value.sinh())
+        Self::new(Float::sinh(self.value))
     }
     #[inline]
     fn cosh(self) -> Self {
-        Self::new(self.value.cosh())
+        Self::new(Float::cosh(self.value))
     }
     #[inline]
     fn tanh(self) -> Self {
-        Self::new(self.value.tanh())
+        Self::new(Float::tanh(self.value))
     }
     #[inline]
     fn asinh(self) -> Self {
-        Self::new(self.value.asinh())
+        Self::new(Float::asinh(self.value))
     }
     #[inline]
     fn acosh(self) -> Self {
-        Self::new(self.value.acosh())
+        Self::new(Float::acosh(self.value))
     }
     #[inline]
     fn atanh(self) -> Self {
-        Self::new(self.value.atanh())
+        Self::new(Float::atanh(self.value))
     }
     #[inline]
     fn integer_decode(self) -> (u64, i16, i8) {
-        self.value.integer_decode()
+        Float::integer_decode(self.value)
     }
     #[inline]
     fn epsilon() -> Self {
-        Self::new(F::epsilon())
+        Self::new(<F as Float>::epsilon())
     }
     #[inline]
     fn to_degrees(self) -> Self {
-        Self::new(self.value.to_degrees())
+        Self::new(Float::to_degrees(self.value))
     }
     #[inline]
     fn to_radians(self) -> Self {
-        Self::new(self.value.to_radians())
+        Self::new(Float::to_radians(self.value))
     }
 }
 
-impl<F: Float + FloatConst, C: FloatChecker<F>> FloatConst for NoisyFloat<F, C> {
+impl<F: FloatCore + FloatConst, C: FloatChecker<F>> FloatConst for NoisyFloat<F, C> {
     #[inline]
     fn E() -> Self {
         Self::new(F::E())
@@ -949,18 +1121,18 @@ impl<F: Float + FloatConst, C: FloatChecker<F>> FloatConst for NoisyFloat<F, C>
     }
 }
 
-impl<F: Float + Signed, C: FloatChecker<F>> Signed for NoisyFloat<F, C> {
+impl<F: FloatCore + Signed, C: FloatChecker<F>> Signed for NoisyFloat<F, C> {
     #[inline]
     fn abs(&self) -> Self {
-        Self::new(self.value.abs())
+        Self::new(Signed::abs(&self.value))
     }
     #[inline]
     fn abs_sub(&self, other: &Self) -> Self {
-        Self::new(self.value.abs_sub(other.value))
+        Self::new(self.value.abs_sub(&other.value))
     }
     #[inline]
     fn signum(&self) -> Self {
-        Self::new(self.value.signum())
+        Self::new(Signed::signum(&self.value))
     }
     #[inline]
     fn is_positive(&self) -> bool {
@@ -972,18 +1144,18 @@ impl<F: Float + Signed, C: FloatChecker<F>> Signed for NoisyFloat<F, C> {
     }
 }
 
-impl<F: Float + Bounded, C: FloatChecker<F>> Bounded for NoisyFloat<F, C> {
+impl<F: FloatCore + Bounded, C: FloatChecker<F>> Bounded for NoisyFloat<F, C> {
     #[inline]
     fn min_value() -> Self {
-        Self::new(Float::min_value())
+        Self::new(FloatCore::min_value())
     }
     #[inline]
     fn max_value() -> Self {
-        Self::new(Float::max_value())
+        Self::new(FloatCore::max_value())
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> iter::Sum for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> iter::Sum for NoisyFloat<F, C> {
     fn sum<I>(iter: I) -> Self
     where
         I: Iterator<Item = Self>,
@@ -992,7 +1164,7 @@ impl<F: Float, C: FloatChecker<F>> iter::Sum for NoisyFloat<F, C> {
     }
 }
 
-impl<'a, F: Float, C: FloatChecker<F>> iter::Sum<&'a Self> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore, C: FloatChecker<F>> iter::Sum<&'a Self> for NoisyFloat<F, C> {
     fn sum<I>(iter: I) -> Self
     where
         I: Iterator<Item = &'a Self>,
@@ -1001,7 +1173,7 @@ impl<'a, F: Float, C: FloatChecker<F>> iter::Sum<&'a Self> for NoisyFloat<F, C>
     }
 }
 
-impl<F: Float, C: FloatChecker<F>> iter::Product for NoisyFloat<F, C> {
+impl<F: FloatCore, C: FloatChecker<F>> iter::Product for NoisyFloat<F, C> {
     fn product<I>(iter: I) -> Self
     where
         I: Iterator<Item = Self>,
@@ -1010,7 +1182,7 @@ impl<F: Float, C: FloatChecker<F>> iter::Product for NoisyFloat<F, C> {
     }
 }
 
-impl<'a, F: Float, C: FloatChecker<F>> iter::Product<&'a Self> for NoisyFloat<F, C> {
+impl<'a, F: FloatCore, C: FloatChecker<F>> iter::Product<&'a Self> for NoisyFloat<F, C> {
     fn product<I>(iter: I) -> Self
     where
         I: Iterator<Item = &'a Self>,
@@ -1026,7 +1198,7 @@ mod approx_impl {
 
     impl<F, C> AbsDiffEq<Self> for NoisyFloat<F, C>
     where
-        F: Float + AbsDiffEq<Epsilon = F>,
+        F: FloatCore + AbsDiffEq<Epsilon = F>,
         C: FloatChecker<F>,
     {
         type Epsilon = NoisyFloat<F, C>;
@@ -1042,7 +1214,7 @@ mod approx_impl {
 
     impl<F, C> RelativeEq<Self> for NoisyFloat<F, C>
     where
-        F: Float + RelativeEq<Epsilon = F>,
+        F: FloatCore + RelativeEq<Epsilon = F>,
         C: FloatChecker<F>,
     {
         fn default_max_relative() -> Self::Epsilon {
@@ -1062,7 +1234,7 @@ mod approx_impl {
 
     impl<F, C> UlpsEq<Self> for NoisyFloat<F, C>
     where
-        F: Float + UlpsEq<Epsilon = F>,
+        F: FloatCore + UlpsEq<Epsilon = F>,
         C: FloatChecker<F>,
     {
         fn default_max_ulps() -> u32 {
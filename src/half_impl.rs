@@ -0,0 +1,199 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support for the half-precision float types of the `half` crate.
+
+use crate::{FloatChecker, InvalidValueError, NoisyFloat, RawFloat};
+#[cfg(feature = "serde-1")]
+use core::any::TypeId;
+use core::convert::TryFrom;
+use half::{bf16, f16};
+
+impl RawFloat for f16 {
+    type Bits = u16;
+    #[inline]
+    fn to_bits(self) -> u16 {
+        f16::to_bits(self)
+    }
+    #[inline]
+    fn from_bits(bits: u16) -> Self {
+        f16::from_bits(bits)
+    }
+}
+
+impl RawFloat for bf16 {
+    type Bits = u16;
+    #[inline]
+    fn to_bits(self) -> u16 {
+        bf16::to_bits(self)
+    }
+    #[inline]
+    fn from_bits(bits: u16) -> Self {
+        bf16::from_bits(bits)
+    }
+}
+
+/// Returns whether the float type is one of the `half` types.
+///
+/// These serialize as their raw bits, so `NoisyFloat` widens them to `f32`
+/// to serialize them as floats instead.
+#[cfg(feature = "serde-1")]
+#[inline]
+pub(crate) fn serde_widens<F: 'static>() -> bool {
+    let id = TypeId::of::<F>();
+    id == TypeId::of::<f16>() || id == TypeId::of::<bf16>()
+}
+
+crate::impl_float_conversions!(f16);
+crate::impl_float_conversions!(bf16);
+
+macro_rules! impl_widening {
+    ($half:ty, $wide:ty, $to_wide:ident) => {
+        impl<C: FloatChecker<$half>> From<NoisyFloat<$half, C>> for $wide {
+            #[inline]
+            fn from(n: NoisyFloat<$half, C>) -> Self {
+                n.value.$to_wide()
+            }
+        }
+
+        impl<C: FloatChecker<$half> + FloatChecker<$wide>> From<NoisyFloat<$half, C>>
+            for NoisyFloat<$wide, C>
+        {
+            /// Widens the value, which is always exact.
+            #[inline]
+            fn from(n: NoisyFloat<$half, C>) -> Self {
+                Self::new(n.value.$to_wide())
+            }
+        }
+    };
+}
+
+impl_widening!(f16, f32, to_f32);
+impl_widening!(f16, f64, to_f64);
+impl_widening!(bf16, f32, to_f32);
+impl_widening!(bf16, f64, to_f64);
+
+macro_rules! impl_narrowing {
+    ($wide:ty, $half:ty, $from_wide:path) => {
+        impl<C: FloatChecker<$wide> + FloatChecker<$half>> TryFrom<NoisyFloat<$wide, C>>
+            for NoisyFloat<$half, C>
+        {
+            type Error = InvalidValueError;
+
+            /// Rounds the value to the nearest representable value.
+            ///
+            /// Fails if a finite value overflows to infinity, or if the
+            /// rounded value is rejected by the checker.
+            #[inline]
+            fn try_from(n: NoisyFloat<$wide, C>) -> Result<Self, Self::Error> {
+                let value = $from_wide(n.value);
+                if n.value.is_finite() && value.is_infinite() {
+                    return Err(InvalidValueError);
+                }
+                Self::try_new(value).ok_or(InvalidValueError)
+            }
+        }
+    };
+}
+
+impl_narrowing!(f32, f16, f16::from_f32);
+impl_narrowing!(f64, f16, f16::from_f64);
+impl_narrowing!(f32, bf16, bf16::from_f32);
+impl_narrowing!(f64, bf16, bf16::from_f64);
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use crate::{prelude::*, InvalidValueError};
+    use core::{
+        convert::TryFrom,
+        hash::{Hash, Hasher},
+    };
+    use half::{bf16, f16};
+    use std::collections::hash_map::DefaultHasher;
+
+    fn hash<T: Hash>(value: T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn half_precision() {
+        let value = r16(f16::from_f32(1.5));
+        assert_eq!(R32::from(value), r32(1.5));
+        assert_eq!(R64::from(rb16(bf16::from_f32(-2.0))), r64(-2.0));
+        assert_eq!(f32::from(value), 1.5);
+        assert_eq!(N32::from(N16::new(f16::INFINITY)), N32::INFINITY);
+
+        assert_eq!(R16::try_from(r32(0.25)), Ok(r16(f16::from_f32(0.25))));
+        assert_eq!(RB16::try_from(r64(3.0)), Ok(rb16(bf16::from_f32(3.0))));
+        assert_eq!(R16::try_from(r32(65504.0)), Ok(r16(f16::MAX)));
+        assert_eq!(R16::try_from(r32(1.0e5)), Err(InvalidValueError));
+        assert_eq!(N16::try_from(n32(1.0e5)), Err(InvalidValueError));
+        assert_eq!(
+            N16::try_from(N32::NEG_INFINITY),
+            Ok(N16::new(f16::NEG_INFINITY))
+        );
+        assert_eq!(RB16::try_from(R32::MAX), Err(InvalidValueError));
+        assert_eq!(R16::try_from(f16::NAN), Err(InvalidValueError));
+
+        assert_eq!(hash(r16(f16::from_f32(-0.0))), hash(f16::ZERO.to_bits()));
+        assert_eq!(hash(rb16(bf16::ONE)), hash(bf16::ONE.to_bits()));
+    }
+
+    #[test]
+    fn bytes() {
+        let value = r16(f16::from_f32(-1.5));
+        assert_eq!(value.to_le_bytes(), f16::from_f32(-1.5).to_le_bytes());
+        assert_eq!(R16::try_from_be_bytes(value.to_be_bytes()), Ok(value));
+        assert_eq!(
+            RB16::try_from_le_bytes(bf16::NAN.to_le_bytes()),
+            Err(InvalidValueError)
+        );
+        assert!(value.to_ordered_bytes() < r16(f16::ZERO).to_ordered_bytes());
+        assert_eq!(R16::try_from(crate::OrderedKey::from(value)), Ok(value));
+    }
+
+    #[cfg(feature = "serde-1")]
+    #[test]
+    fn serialize_half_precision() {
+        let value = r16(f16::from_f32(0.5));
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, "0.5");
+        assert_eq!(serde_json::from_str::<R16>(&json).unwrap(), value);
+        assert_eq!(
+            serde_json::from_str::<R16>("-2").unwrap(),
+            r16(f16::from_f32(-2.0))
+        );
+        assert_eq!(
+            serde_json::to_string(&rb16(bf16::from_f32(1.5))).unwrap(),
+            "1.5"
+        );
+        assert_eq!(
+            serde_json::from_str::<NB16>("0.1").unwrap(),
+            NB16::new(bf16::from_f32(0.1))
+        );
+        assert_eq!(serde_json::from_str::<R16>("65504").unwrap(), r16(f16::MAX));
+
+        // out of range for f16, rather than rejected as a malformed u16
+        let error = serde_json::from_str::<R16>("1e5").unwrap_err();
+        assert!(std::format!("{}", error).contains("illegal value"));
+        assert!(serde_json::from_str::<N16>("1e5").is_err());
+        assert_eq!(
+            serde_json::from_str::<R16>("14336").unwrap(),
+            r16(f16::from_f32(14336.0))
+        );
+    }
+}
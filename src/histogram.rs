@@ -0,0 +1,348 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Histograms of `NoisyFloat` values.
+//!
+//! ```
+//! use noisy_float::{histogram::Histogram, prelude::*};
+//!
+//! let mut histogram = Histogram::uniform(r64(0.0), r64(10.0), 5).unwrap();
+//! histogram.extend([-1.0, 0.0, 3.5, 9.0, 10.0, 12.0].iter().map(|&x| r64(x)));
+//! assert_eq!(histogram.counts(), &[1, 1, 0, 0, 2]);
+//! assert_eq!(histogram.underflow(), 1);
+//! assert_eq!(histogram.overflow(), 1);
+//! ```
+//!
+//! This module requires the `std` feature.
+
+use crate::{iter::count_to_float, FloatChecker, NoisyFloat};
+use core::fmt;
+use num_traits::float::FloatCore;
+use std::{vec, vec::Vec};
+
+/// The error returned when constructing or merging a `Histogram` fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HistogramError {
+    /// The number of uniform bins is zero.
+    NoBins,
+    /// The range of uniform bins is empty, or its width is not finite.
+    InvalidRange,
+    /// Fewer than two edges were given.
+    TooFewEdges,
+    /// The edges are not strictly increasing.
+    UnsortedEdges {
+        /// The index of the first edge that is not larger than the previous one.
+        index: usize,
+    },
+    /// The histograms to merge have different edges.
+    MismatchedEdges,
+}
+
+impl fmt::Display for HistogramError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            HistogramError::NoBins => write!(f, "histogram without bins"),
+            HistogramError::InvalidRange => write!(f, "invalid histogram range"),
+            HistogramError::TooFewEdges => write!(f, "histogram with fewer than two edges"),
+            HistogramError::UnsortedEdges { index } => {
+                write!(f, "histogram edge at index {} is not increasing", index)
+            }
+            HistogramError::MismatchedEdges => write!(f, "histograms have different edges"),
+        }
+    }
+}
+
+impl std::error::Error for HistogramError {}
+
+/// A histogram counting values in bins between consecutive edges.
+///
+/// Bin `i` holds the values `v` with `edges[i] <= v < edges[i + 1]`, except
+/// that the last bin also holds the values equal to the last edge. Values
+/// below the first edge or above the last edge, such as infinite values when
+/// the edges are finite, are counted in separate underflow and overflow bins.
+///
+/// Since `NoisyFloat` values are totally ordered, the bin of a value is found
+/// by binary search over the edges. `-0.0` and `+0.0` fall into the same bin.
+pub struct Histogram<F: FloatCore, C: FloatChecker<F>> {
+    edges: Vec<NoisyFloat<F, C>>,
+    counts: Vec<u64>,
+    underflow: u64,
+    overflow: u64,
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Histogram<F, C> {
+    /// Constructs a histogram with `bins` bins of equal width from `min` to `max`.
+    ///
+    /// Returns an error if `bins` is zero, or if `min` is not less than `max`
+    /// or the width of the range is not finite.
+    pub fn uniform(
+        min: NoisyFloat<F, C>,
+        max: NoisyFloat<F, C>,
+        bins: usize,
+    ) -> Result<Self, HistogramError> {
+        if bins == 0 {
+            return Err(HistogramError::NoBins);
+        }
+        if min >= max || !(max.raw() - min.raw()).is_finite() {
+            return Err(HistogramError::InvalidRange);
+        }
+        let count = count_to_float::<F>(bins);
+        let mut edges: Vec<_> = (0..bins)
+            .map(|i| {
+                let t = count_to_float::<F>(i) / count;
+                NoisyFloat::new(min.raw() + (max.raw() - min.raw()) * t)
+            })
+            .collect();
+        edges.push(max);
+        Self::with_edges(edges)
+    }
+
+    /// Constructs a histogram with the given bin edges.
+    ///
+    /// Returns an error if there are fewer than two edges,
+    /// or if the edges are not strictly increasing.
+    pub fn with_edges(edges: Vec<NoisyFloat<F, C>>) -> Result<Self, HistogramError> {
+        if edges.len() < 2 {
+            return Err(HistogramError::TooFewEdges);
+        }
+        if let Some(index) = edges.windows(2).position(|pair| pair[0] >= pair[1]) {
+            return Err(HistogramError::UnsortedEdges { index: index + 1 });
+        }
+        Ok(Histogram {
+            counts: vec![0; edges.len() - 1],
+            edges,
+            underflow: 0,
+            overflow: 0,
+        })
+    }
+
+    /// Returns the edges of the bins.
+    #[inline]
+    pub fn edges(&self) -> &[NoisyFloat<F, C>] {
+        &self.edges
+    }
+
+    /// Returns the lower and upper edge of bin `index`.
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn bin_range(&self, index: usize) -> (NoisyFloat<F, C>, NoisyFloat<F, C>) {
+        (self.edges[index], self.edges[index + 1])
+    }
+
+    /// Returns the number of bins, excluding the underflow and overflow bins.
+    #[inline]
+    pub fn bins(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns the index of the bin holding `value`,
+    /// or `None` if it is below the first edge or above the last edge.
+    pub fn find_bin(&self, value: NoisyFloat<F, C>) -> Option<usize> {
+        let below = self.edges.partition_point(|&edge| edge <= value);
+        if below == 0 {
+            None
+        } else if below < self.edges.len() {
+            Some(below - 1)
+        } else if value == self.edges[below - 1] {
+            Some(self.counts.len() - 1)
+        } else {
+            None
+        }
+    }
+
+    /// Adds a value to its bin.
+    #[inline]
+    pub fn add(&mut self, value: NoisyFloat<F, C>) {
+        match self.find_bin(value) {
+            Some(index) => self.counts[index] += 1,
+            None if value < self.edges[0] => self.underflow += 1,
+            None => self.overflow += 1,
+        }
+    }
+
+    /// Returns the number of values in each bin.
+    #[inline]
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Returns the number of values below the first edge.
+    #[inline]
+    pub fn underflow(&self) -> u64 {
+        self.underflow
+    }
+
+    /// Returns the number of values above the last edge.
+    #[inline]
+    pub fn overflow(&self) -> u64 {
+        self.overflow
+    }
+
+    /// Returns the number of values added, including underflow and overflow.
+    pub fn total(&self) -> u64 {
+        self.underflow + self.counts.iter().sum::<u64>() + self.overflow
+    }
+
+    /// Returns, for each bin, the number of values up to its upper edge,
+    /// including the underflow.
+    pub fn cumulative_counts(&self) -> Vec<u64> {
+        self.counts
+            .iter()
+            .scan(self.underflow, |total, &count| {
+                *total += count;
+                Some(*total)
+            })
+            .collect()
+    }
+
+    /// Adds the counts of `other` to `self`.
+    ///
+    /// Returns an error, leaving `self` unchanged,
+    /// if the histograms have different edges.
+    pub fn merge(&mut self, other: &Self) -> Result<(), HistogramError> {
+        if self.edges != other.edges {
+            return Err(HistogramError::MismatchedEdges);
+        }
+        for (count, &other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+        self.underflow += other.underflow;
+        self.overflow += other.overflow;
+        Ok(())
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Clone for Histogram<F, C> {
+    fn clone(&self) -> Self {
+        Histogram {
+            edges: self.edges.clone(),
+            counts: self.counts.clone(),
+            underflow: self.underflow,
+            overflow: self.overflow,
+        }
+    }
+}
+
+impl<F: FloatCore + fmt::Debug, C: FloatChecker<F>> fmt::Debug for Histogram<F, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("Histogram")
+            .field("edges", &self.edges)
+            .field("counts", &self.counts)
+            .field("underflow", &self.underflow)
+            .field("overflow", &self.overflow)
+            .finish()
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Extend<NoisyFloat<F, C>> for Histogram<F, C> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = NoisyFloat<F, C>>>(&mut self, iter: I) {
+        for value in iter {
+            self.add(value);
+        }
+    }
+}
+
+impl<'a, F: FloatCore, C: FloatChecker<F>> Extend<&'a NoisyFloat<F, C>> for Histogram<F, C> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'a NoisyFloat<F, C>>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn uniform() {
+        let histogram = Histogram::uniform(r64(-1.0), r64(1.0), 4).unwrap();
+        assert_eq!(
+            histogram.edges(),
+            &[r64(-1.0), r64(-0.5), r64(0.0), r64(0.5), r64(1.0)]
+        );
+        assert_eq!(histogram.bins(), 4);
+        assert_eq!(histogram.bin_range(1), (r64(-0.5), r64(0.0)));
+
+        let histogram = Histogram::uniform(r64(-f64::MAX), r64(f64::MAX), 2);
+        assert_eq!(histogram.unwrap_err(), HistogramError::InvalidRange);
+        let histogram = Histogram::uniform(r64(1.0), r64(1.0), 2);
+        assert_eq!(histogram.unwrap_err(), HistogramError::InvalidRange);
+        let histogram = Histogram::uniform(n64(0.0), N64::INFINITY, 2);
+        assert_eq!(histogram.unwrap_err(), HistogramError::InvalidRange);
+        let histogram = Histogram::uniform(r64(0.0), r64(1.0), 0);
+        assert_eq!(histogram.unwrap_err(), HistogramError::NoBins);
+    }
+
+    #[test]
+    fn explicit_edges() {
+        let histogram = Histogram::with_edges(vec![r32(0.0)]);
+        assert_eq!(histogram.unwrap_err(), HistogramError::TooFewEdges);
+        let histogram = Histogram::with_edges(vec![r32(0.0), r32(1.0), r32(1.0)]);
+        assert_eq!(
+            histogram.unwrap_err(),
+            HistogramError::UnsortedEdges { index: 2 }
+        );
+        let histogram = Histogram::with_edges(vec![r32(0.0), r32(-0.0)]);
+        assert_eq!(
+            histogram.unwrap_err(),
+            HistogramError::UnsortedEdges { index: 1 }
+        );
+    }
+
+    #[test]
+    fn bins() {
+        let mut histogram = Histogram::with_edges(vec![n64(-1.0), n64(0.0), n64(2.0)]).unwrap();
+        assert_eq!(histogram.find_bin(n64(-1.0)), Some(0));
+        assert_eq!(histogram.find_bin(n64(-0.0)), Some(1));
+        assert_eq!(histogram.find_bin(n64(2.0)), Some(1));
+        assert_eq!(histogram.find_bin(n64(-1.5)), None);
+        histogram.extend(&[
+            N64::NEG_INFINITY,
+            n64(-0.5),
+            n64(0.0),
+            n64(1.0),
+            n64(2.0),
+            N64::INFINITY,
+            N64::INFINITY,
+        ]);
+        assert_eq!(histogram.counts(), &[1, 3]);
+        assert_eq!(histogram.underflow(), 1);
+        assert_eq!(histogram.overflow(), 2);
+        assert_eq!(histogram.total(), 7);
+        assert_eq!(histogram.cumulative_counts(), vec![2, 5]);
+
+        let mut histogram = Histogram::with_edges(vec![n32(0.0), N32::INFINITY]).unwrap();
+        histogram.add(N32::INFINITY);
+        assert_eq!(histogram.counts(), &[1]);
+    }
+
+    #[test]
+    fn merge() {
+        let mut first = Histogram::uniform(r64(0.0), r64(3.0), 3).unwrap();
+        let mut second = first.clone();
+        first.extend(&[r64(0.5), r64(-1.0)]);
+        second.extend(&[r64(0.5), r64(2.5), r64(4.0)]);
+        first.merge(&second).unwrap();
+        assert_eq!(first.counts(), &[2, 0, 1]);
+        assert_eq!(first.underflow(), 1);
+        assert_eq!(first.overflow(), 1);
+
+        let other = Histogram::uniform(r64(0.0), r64(3.0), 2).unwrap();
+        assert_eq!(first.merge(&other), Err(HistogramError::MismatchedEdges));
+        assert_eq!(first.total(), 5);
+    }
+}
@@ -0,0 +1,412 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Extension methods for iterators of `NoisyFloat` values.
+//!
+//! ```
+//! use noisy_float::prelude::*;
+//!
+//! let values = vec![r64(2.0), r64(-1.0), r64(5.0), r64(-1.0)];
+//! assert_eq!(values.iter().argmin(), Some(1));
+//! assert_eq!(values.iter().argmax(), Some(2));
+//! assert_eq!(values.iter().minmax(), Some((r64(-1.0), r64(5.0))));
+//! assert_eq!(values.iter().mean(), Some(r64(1.25)));
+//!
+//! let empty: Vec<R64> = Vec::new();
+//! assert_eq!(empty.iter().mean(), None);
+//! ```
+
+use crate::{checkers::NumChecker, sum::NeumaierSum, FloatChecker, NoisyFloat};
+use core::{borrow::Borrow, fmt, iter::FusedIterator, marker::PhantomData};
+#[cfg(any(feature = "std", feature = "libm"))]
+use num_traits::Float;
+use num_traits::{cast::NumCast, float::FloatCore};
+
+/// Extension methods for iterators of `NoisyFloat` values.
+///
+/// This is implemented for every iterator whose items are `NoisyFloat` values
+/// or references to them. Methods that have no meaningful result for an empty
+/// iterator, or for a sum of zero, return `None` in that case.
+pub trait NoisyIteratorExt<F: FloatCore, C: FloatChecker<F>>: Iterator + Sized
+where
+    Self::Item: Borrow<NoisyFloat<F, C>>,
+{
+    /// Returns the index of the first minimum value,
+    /// or `None` if the iterator is empty.
+    fn argmin(self) -> Option<usize> {
+        let mut best: Option<(usize, NoisyFloat<F, C>)> = None;
+        for (index, value) in self.enumerate() {
+            let value = *value.borrow();
+            match best {
+                Some((_, min)) if min <= value => {}
+                _ => best = Some((index, value)),
+            }
+        }
+        best.map(|(index, _)| index)
+    }
+
+    /// Returns the index of the first maximum value,
+    /// or `None` if the iterator is empty.
+    fn argmax(self) -> Option<usize> {
+        let mut best: Option<(usize, NoisyFloat<F, C>)> = None;
+        for (index, value) in self.enumerate() {
+            let value = *value.borrow();
+            match best {
+                Some((_, max)) if max >= value => {}
+                _ => best = Some((index, value)),
+            }
+        }
+        best.map(|(index, _)| index)
+    }
+
+    /// Returns the minimum and maximum values in a single pass,
+    /// or `None` if the iterator is empty.
+    fn minmax(mut self) -> Option<(NoisyFloat<F, C>, NoisyFloat<F, C>)> {
+        let first = *self.next()?.borrow();
+        Some(self.fold((first, first), |(min, max), value| {
+            let value = *value.borrow();
+            (min.min(value), max.max(value))
+        }))
+    }
+
+    /// Returns the arithmetic mean of the values,
+    /// or `None` if the iterator is empty.
+    ///
+    /// The values are summed with `NeumaierSum`, and the `FloatChecker`
+    /// is used to assert that the mean is valid. The sum is not validated,
+    /// so e.g. the mean of `R64::MAX` and `R64::MAX` is `R64::MAX`.
+    fn mean(self) -> Option<NoisyFloat<F, C>> {
+        let mut sum = NeumaierSum::<F, NumChecker>::new();
+        let mut count = 0usize;
+        let (mut min, mut max) = (F::infinity(), F::neg_infinity());
+        for value in self {
+            let value = value.borrow().raw();
+            sum.add(value);
+            min = min.min(value);
+            max = max.max(value);
+            count += 1;
+        }
+        if count == 0 {
+            return None;
+        }
+        let mean = sum.quotient(count_to_float::<F>(count));
+        // rounding can move a mean next to the largest magnitude out of range
+        let mean = if mean < min {
+            min
+        } else if mean > max {
+            max
+        } else {
+            mean
+        };
+        Some(NoisyFloat::new(mean))
+    }
+
+    /// Returns the geometric mean of the values, i.e. the `n`th root
+    /// of their product, computed from the mean of their logarithms.
+    ///
+    /// Returns `None` if the iterator is empty, if a value is negative,
+    /// or if the values include both zero and infinity.
+    ///
+    /// Requires either the `std` or the `libm` feature.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    fn geometric_mean(self) -> Option<NoisyFloat<F, C>>
+    where
+        F: Float,
+    {
+        let mut logs = NeumaierSum::<F, NumChecker>::new();
+        let mut count = 0usize;
+        let mut has_zero = false;
+        for value in self {
+            let value = value.borrow().raw();
+            if value < F::zero() {
+                return None;
+            } else if value == F::zero() {
+                has_zero = true;
+            } else {
+                logs.add(Float::ln(value));
+            }
+            count += 1;
+        }
+        if count == 0 {
+            return None;
+        }
+        let logs = logs.try_sum()?.raw();
+        if has_zero {
+            return if logs == Float::infinity() {
+                None
+            } else {
+                Some(NoisyFloat::new(F::zero()))
+            };
+        }
+        NoisyFloat::try_new(Float::exp(logs / count_to_float::<F>(count)))
+    }
+
+    /// Returns the harmonic mean of the values, i.e. the reciprocal
+    /// of the mean of their reciprocals.
+    ///
+    /// Returns `None` if the iterator is empty or if the sum of the
+    /// reciprocals is zero or undefined.
+    fn harmonic_mean(self) -> Option<NoisyFloat<F, C>> {
+        let mut reciprocals = NeumaierSum::<F, NumChecker>::new();
+        let mut count = 0usize;
+        for value in self {
+            reciprocals.add(value.borrow().raw().recip());
+            count += 1;
+        }
+        let reciprocals = reciprocals.try_sum()?.raw();
+        if count == 0 || reciprocals == F::zero() {
+            return None;
+        }
+        NoisyFloat::try_new(count_to_float::<F>(count) / reciprocals)
+    }
+
+    /// Returns an iterator over the values divided by their sum,
+    /// or `None` if the sum is zero or undefined, which includes an empty
+    /// iterator.
+    ///
+    /// The sum is computed with `NeumaierSum` on a clone of the iterator.
+    /// It is not checked itself, so values whose sum overflows can still be
+    /// normalized, but the `FloatChecker` is used to assert that each
+    /// normalized value is valid.
+    ///
+    /// ```
+    /// use noisy_float::prelude::*;
+    ///
+    /// let weights = [r64(1.0), r64(3.0)];
+    /// let normalized: Vec<R64> = weights.iter().normalized().unwrap().collect();
+    /// assert_eq!(normalized, vec![r64(0.25), r64(0.75)]);
+    /// assert!([r64(1.0), r64(-1.0)].iter().normalized().is_none());
+    /// ```
+    fn normalized(self) -> Option<Normalized<Self, F, C>>
+    where
+        Self: Clone,
+    {
+        let sum: NeumaierSum<F, NumChecker> =
+            self.clone().map(|value| value.borrow().raw()).collect();
+        if sum.try_sum()?.raw() == F::zero() {
+            return None;
+        }
+        Some(Normalized {
+            iter: self,
+            sum,
+            checker: PhantomData,
+        })
+    }
+}
+
+impl<I, F, C> NoisyIteratorExt<F, C> for I
+where
+    I: Iterator,
+    I::Item: Borrow<NoisyFloat<F, C>>,
+    F: FloatCore,
+    C: FloatChecker<F>,
+{
+}
+
+/// Converts a count to the float type.
+///
+/// Counts beyond the range of the float type, such as those above 65504
+/// for `f16`, convert to positive infinity, so dividing by them gives zero.
+#[inline]
+pub(crate) fn count_to_float<F: FloatCore>(count: usize) -> F {
+    <F as NumCast>::from(count).unwrap_or_else(F::infinity)
+}
+
+/// An iterator over values divided by their sum.
+///
+/// This struct is created by `NoisyIteratorExt::normalized`.
+pub struct Normalized<I, F: FloatCore, C: FloatChecker<F>> {
+    iter: I,
+    sum: NeumaierSum<F, NumChecker>,
+    checker: PhantomData<C>,
+}
+
+impl<I: Clone, F: FloatCore, C: FloatChecker<F>> Clone for Normalized<I, F, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Normalized {
+            iter: self.iter.clone(),
+            sum: self.sum,
+            checker: PhantomData,
+        }
+    }
+}
+
+impl<I: fmt::Debug, F: FloatCore + fmt::Debug, C: FloatChecker<F>> fmt::Debug
+    for Normalized<I, F, C>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("Normalized")
+            .field("iter", &self.iter)
+            .field("sum", &self.sum)
+            .finish()
+    }
+}
+
+impl<I, F, C> Iterator for Normalized<I, F, C>
+where
+    I: Iterator,
+    I::Item: Borrow<NoisyFloat<F, C>>,
+    F: FloatCore,
+    C: FloatChecker<F>,
+{
+    type Item = NoisyFloat<F, C>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|value| NoisyFloat::new(self.sum.divide(value.borrow().raw())))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I, F, C> DoubleEndedIterator for Normalized<I, F, C>
+where
+    I: DoubleEndedIterator,
+    I::Item: Borrow<NoisyFloat<F, C>>,
+    F: FloatCore,
+    C: FloatChecker<F>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next_back()
+            .map(|value| NoisyFloat::new(self.sum.divide(value.borrow().raw())))
+    }
+}
+
+impl<I, F, C> ExactSizeIterator for Normalized<I, F, C>
+where
+    I: ExactSizeIterator,
+    I::Item: Borrow<NoisyFloat<F, C>>,
+    F: FloatCore,
+    C: FloatChecker<F>,
+{
+}
+
+impl<I, F, C> FusedIterator for Normalized<I, F, C>
+where
+    I: FusedIterator,
+    I::Item: Borrow<NoisyFloat<F, C>>,
+    F: FloatCore,
+    C: FloatChecker<F>,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn arg_extrema() {
+        let values = [r64(3.0), r64(-2.0), r64(7.0), r64(-2.0), r64(7.0)];
+        assert_eq!(values.iter().argmin(), Some(1));
+        assert_eq!(values.iter().argmax(), Some(2));
+        assert_eq!(values.iter().copied().argmin(), Some(1));
+        assert_eq!(values.iter().minmax(), Some((r64(-2.0), r64(7.0))));
+        assert_eq!([n32(-0.0), n32(0.0)].iter().argmax(), Some(0));
+
+        let empty: [R64; 0] = [];
+        assert_eq!(empty.iter().argmin(), None);
+        assert_eq!(empty.iter().argmax(), None);
+        assert_eq!(empty.iter().minmax(), None);
+    }
+
+    #[test]
+    fn means() {
+        let values = [r64(1.0), r64(2.0), r64(4.0)];
+        assert_eq!(values.iter().mean(), Some(r64(7.0 / 3.0)));
+        assert_eq!(values.iter().harmonic_mean(), Some(r64(12.0 / 7.0)));
+        assert_eq!([r32(0.0), r32(1.0)].iter().harmonic_mean(), Some(r32(0.0)));
+        assert_eq!([r32(1.0), r32(-1.0)].iter().harmonic_mean(), None);
+
+        let empty: [R64; 0] = [];
+        assert_eq!(empty.iter().mean(), None);
+        assert_eq!(empty.iter().harmonic_mean(), None);
+    }
+
+    #[test]
+    fn mean_overflow() {
+        assert_eq!([R64::MAX, R64::MAX].iter().mean(), Some(R64::MAX));
+        assert_eq!([R64::MIN, R64::MIN, R64::MIN].iter().mean(), Some(R64::MIN));
+        assert_eq!([R64::MAX; 1000].iter().mean(), Some(R64::MAX));
+        let values = [R32::MAX, R32::MAX, r32(0.0), r32(0.0)];
+        assert_eq!(values.iter().mean(), Some(R32::MAX / 2.0));
+        assert_eq!([n64(1.0), N64::INFINITY].iter().mean(), Some(N64::INFINITY));
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn geometric_mean() {
+        let values = [r64(1.0), r64(2.0), r64(4.0)];
+        let mean = values.iter().geometric_mean().unwrap();
+        assert!((mean - 2.0).abs() < 1e-12);
+        assert_eq!([r64(0.0), r64(5.0)].iter().geometric_mean(), Some(r64(0.0)));
+        assert_eq!([r64(-1.0), r64(5.0)].iter().geometric_mean(), None);
+        assert_eq!([n64(0.0), N64::INFINITY].iter().geometric_mean(), None);
+        assert_eq!(
+            [n64(1.0), N64::INFINITY].iter().geometric_mean(),
+            Some(N64::INFINITY)
+        );
+        let empty: [R64; 0] = [];
+        assert_eq!(empty.iter().geometric_mean(), None);
+    }
+
+    #[test]
+    fn normalized() {
+        let values = [r32(1.0), r32(2.0), r32(5.0)];
+        let mut normalized = values.iter().normalized().unwrap();
+        assert_eq!(normalized.len(), 3);
+        assert_eq!(normalized.clone().count(), 3);
+        assert_eq!(normalized.next_back(), Some(r32(0.625)));
+        assert_eq!(normalized.next(), Some(r32(0.125)));
+        assert_eq!(normalized.next(), Some(r32(0.25)));
+        assert_eq!(normalized.next(), None);
+
+        let empty: [R32; 0] = [];
+        assert!(empty.iter().normalized().is_none());
+        assert!([r32(2.0), r32(-2.0)].iter().normalized().is_none());
+        assert!([N32::INFINITY, N32::NEG_INFINITY]
+            .iter()
+            .normalized()
+            .is_none());
+    }
+
+    #[test]
+    fn normalized_overflow() {
+        let values = [r64(f64::MAX), r64(f64::MAX)];
+        assert!(values.iter().normalized().unwrap().eq([r64(0.5), r64(0.5)]));
+        let values = [R32::MAX, R32::MAX, R32::MIN, r32(f32::MIN / 2.0)];
+        let expected = [r32(2.0), r32(2.0), r32(-2.0), r32(-1.0)];
+        assert!(values.iter().normalized().unwrap().eq(expected));
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn count_beyond_float_range() {
+        use half::f16;
+
+        assert_eq!(count_to_float::<f16>(65504), f16::MAX);
+        assert_eq!(count_to_float::<f16>(70000), f16::INFINITY);
+        // the mean stays between the smallest and largest value
+        let values = core::iter::repeat_n(n16(f16::ONE), 70000);
+        assert_eq!(values.mean(), Some(n16(f16::ONE)));
+    }
+}
@@ -33,12 +33,14 @@
 //!
 //! This crate makes use of the num, bounded, signed and floating point traits
 //! in the popular `num_traits` crate.
-//! This crate can be compiled with no_std.
+//! This crate can be compiled with no_std; see the `libm` feature below.
 //!
 //! # Examples
 //! An example using the `R64` type, which corresponds to *finite* `f64` values.
 //!
 //! ```
+//! # #[cfg(any(feature = "std", feature = "libm"))]
+//! # {
 //! use noisy_float::prelude::*;
 //!
 //! fn geometric_mean(a: R64, b: R64) -> R64 {
@@ -55,6 +57,7 @@
 //! );
 //! //prints 14.142...
 //! assert!(mean(r64(10.0), r64(20.0)) == 15.0);
+//! # }
 //! ```
 //!
 //! An example using the `N32` type, which corresponds to *non-NaN* `f32` values.
@@ -84,37 +87,126 @@
 //! assert!(value_f64_a as u64 == value_u64);
 //! ```
 //!
+//! # Custom float types
+//!
+//! `NoisyFloat` is not limited to `f32` and `f64`. Any type implementing
+//! `num_traits::float::FloatCore` can be used as the underlying float type,
+//! which provides the arithmetic operators, `Ord`, the `num_traits` number
+//! traits and the `FloatCore` methods. Beyond that:
+//!
+//! - Implementing `num_traits::Float` (and `FloatConst`, `Signed`, `Bounded`)
+//!   for the float type enables the corresponding impls for `NoisyFloat`.
+//! - Implementing `RawFloat` enables `Hash`, and the other functionality of
+//!   this crate that works on the binary representation of floats.
+//! - Invoking `impl_float_conversions!` in the crate defining the float type
+//!   provides `From`/`TryFrom` conversions to and from `NoisyFloat`,
+//!   which coherence rules do not allow this crate to implement generically.
+//!
+//! The checkers in `noisy_float::checkers` only rely on `FloatCore`,
+//! so e.g. `NoisyFloat<MyFloat, FiniteChecker>` works like `R64`.
+//!
 //! # Features
 //!
 //! This crate has the following cargo features:
 //!
+//! - `std` (enabled by default): Use the standard library for the floating
+//!   point math functions of `num_traits::Float`, and implement
+//!   `std::error::Error` for the error types of this crate.
+//!   This implies the `alloc` feature.
+//! - `alloc`: Use the `alloc` crate for the bulk byte decoding functions,
+//!   such as `try_vec_from_le_bytes`, so that they are available with no_std.
+//! - `libm`: Use the `libm` crate for the floating point math functions of
+//!   `num_traits::Float`, so that they are available with no_std.
+//!   Without either `std` or `libm`, `NoisyFloat` only implements
+//!   `num_traits::float::FloatCore`.
 //! - `serde-1`: Enable serialization for all `NoisyFloats` using serde 1.0 and
 //!   will transparently serialize then as floats
+//! - `half`: Add the half-precision types `N16`, `R16`, `NB16` and `RB16`
+//!   using the `f16` and `bf16` types of the `half` crate. They widen
+//!   losslessly into the 32- and 64-bit types with `From`, and the 32- and
+//!   64-bit types narrow into them with `TryFrom`, which fails on overflow.
+//! - `rayon`: Add `sort::par_radix_sort`, which sorts slices in parallel
+//!   using the `rayon` crate. This implies the `std` feature.
+//! - `rand`: Sample the 32- and 64-bit `NoisyFloat` types with the `rand`
+//!   crate, using the `Standard`, `Open01` and `OpenClosed01` distributions,
+//!   or uniformly from a range with `Rng::gen_range`.
+//! - `proptest`: Implement `Arbitrary` for the 32- and 64-bit `NoisyFloat`
+//!   types, and add strategies in `noisy_float::strategy` that favor edge
+//!   values such as `±0`, subnormals and the largest finite values.
+//!   This implies the `std` feature.
 
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 #[cfg(feature = "serde-1")]
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
+mod atomic;
+mod bytes;
 pub mod checkers;
 mod float_impl;
+#[cfg(feature = "half")]
+mod half_impl;
+#[cfg(feature = "std")]
+pub mod histogram;
+pub mod iter;
+#[cfg(feature = "std")]
+pub mod lookup;
+mod optional;
+#[cfg(feature = "rand")]
+mod rand_impl;
+pub mod range;
+pub mod solve;
+#[cfg(feature = "std")]
+pub mod sort;
+pub mod stats;
+#[cfg(feature = "proptest")]
+pub mod strategy;
+pub mod sum;
 pub mod types;
 
+pub use crate::atomic::{AtomicFloat, AtomicNoisyFloat};
+pub use crate::bytes::{FromBytesError, OrderedKey};
+pub use crate::optional::OptionalFloat;
+#[cfg(feature = "rand")]
+pub use crate::rand_impl::UniformNoisyFloat;
+
 /// Prelude for the `noisy_float` crate.
 ///
-/// This includes all of the types defined in the `noisy_float::types` module,
+/// This includes all of the types defined in the `noisy_float::types` module
+/// and the literal macros `n32!`, `n64!`, `r32!` and `r64!`, the `SumExt`
+/// extension trait for accurate summation, the `NoisyIteratorExt` extension
+/// trait for statistics over iterators,
 /// as well as a re-export of the `Float` trait from the `num_traits` crate.
-/// It is important to have this re-export here, because it allows the user
-/// to access common floating point methods like `abs()`, `sqrt()`, etc.
+/// The common floating point methods like `abs()`, `sqrt()`, etc. are also
+/// available as inherent methods on `NoisyFloat`, so this re-export is mostly
+/// useful for writing code that is generic over the float type.
+///
+/// When neither the `std` nor the `libm` feature is enabled, `Float` is not
+/// available, and the `FloatCore` trait is re-exported instead.
 pub mod prelude {
+    pub use crate::iter::NoisyIteratorExt;
+    pub use crate::sum::SumExt;
     pub use crate::types::*;
+    pub use crate::{n32, n64, r32, r64};
 
+    #[cfg(any(feature = "std", feature = "libm"))]
     #[doc(no_inline)]
     pub use num_traits::Float;
+
+    #[cfg(not(any(feature = "std", feature = "libm")))]
+    #[doc(no_inline)]
+    pub use num_traits::float::FloatCore;
 }
 
-use core::{fmt, marker::PhantomData};
+use core::{fmt, hash::Hash, marker::PhantomData};
+#[cfg(any(feature = "std", feature = "libm"))]
 use num_traits::Float;
+use num_traits::{float::FloatCore, One, PrimInt, Unsigned, Zero};
 
 /// Trait for checking whether a floating point number is *valid*.
 ///
@@ -133,6 +225,46 @@ pub trait FloatChecker<F> {
     fn assert(value: F);
 }
 
+/// Trait for floating point types that can be converted to and from their bit representation.
+///
+/// This is implemented for `f32` and `f64`, and can be implemented for other
+/// float types, such as software floats. It provides the `Hash` impl of
+/// `NoisyFloat`, and the other functionality of this crate
+/// that works on the binary representation of floats, including the byte
+/// conversions and `OrderedKey`, which are available whenever `Bits`
+/// implements `num_traits::ToBytes` and `num_traits::FromBytes`, as all
+/// primitive unsigned integers do.
+///
+/// The bits are expected to use the IEEE 754 layout, with the sign in the
+/// highest bit, so that stepping the bits of a positive value by one yields
+/// the adjacent representable value.
+pub trait RawFloat: FloatCore {
+    /// An unsigned integer type with the same size as the float type.
+    type Bits: PrimInt + Unsigned + Hash + fmt::Debug;
+
+    /// Returns the raw bit representation of the value.
+    fn to_bits(self) -> Self::Bits;
+
+    /// Constructs a value from its raw bit representation.
+    fn from_bits(bits: Self::Bits) -> Self;
+}
+
+/// The error returned when a value is rejected by a `FloatChecker`.
+///
+/// With the `std` feature enabled, this implements `std::error::Error`,
+/// so it can be propagated with `?` into a `Box<dyn std::error::Error>`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InvalidValueError;
+
+impl fmt::Display for InvalidValueError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.write_str("illegal value")
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidValueError {}
+
 /// A floating point number with a restricted set of legal values.
 ///
 /// Typical users will not need to access this struct directly, but
@@ -148,25 +280,45 @@ pub trait FloatChecker<F> {
 /// The exception to this rule is for methods that return an `Option` containing
 /// a `NoisyFloat`, in which case the result would be `None` if the value is invalid.
 #[repr(transparent)]
-pub struct NoisyFloat<F: Float, C: FloatChecker<F>> {
+pub struct NoisyFloat<F: FloatCore, C: FloatChecker<F>> {
     value: F,
     checker: PhantomData<C>,
 }
 
-impl<F: Float, C: FloatChecker<F>> NoisyFloat<F, C> {
+/// Linearly interpolates from `start` at `t == 0` to `end` at `t == 1`,
+/// as `NoisyFloat::lerp` does for raw values.
+#[inline]
+pub(crate) fn lerp<F: FloatCore>(start: F, end: F, t: F) -> F {
+    if t == F::zero() {
+        start
+    } else if t == F::one() {
+        end
+    } else {
+        start * (F::one() - t) + end * t
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> NoisyFloat<F, C> {
     /// Constructs a `NoisyFloat` with the given value.
     ///
     /// Uses the `FloatChecker` to assert that the value is valid.
     #[inline]
     pub fn new(value: F) -> Self {
         C::assert(value);
-        Self::unchecked_new(value)
+        Self::new_unchecked(value)
     }
 
+    /// Constructs a `NoisyFloat` with the given value, without checking it.
+    ///
+    /// Unlike `new`, this is a `const fn`, so it can be used to initialize
+    /// constants and statics. The `FloatChecker` is *not* consulted, not even
+    /// in debug builds, so it is up to the caller to pass a valid value.
+    /// The type aliases in `noisy_float::types` also have a checked
+    /// `const_new` constructor, which should be preferred where available.
     #[inline]
-    fn unchecked_new(value: F) -> Self {
+    pub const fn new_unchecked(value: F) -> Self {
         NoisyFloat {
-            value: value,
+            value,
             checker: PhantomData,
         }
     }
@@ -177,10 +329,7 @@ impl<F: Float, C: FloatChecker<F>> NoisyFloat<F, C> {
     #[inline]
     pub fn try_new(value: F) -> Option<Self> {
         if C::check(value) {
-            Some(NoisyFloat {
-                value: value,
-                checker: PhantomData,
-            })
+            Some(Self::new_unchecked(value))
         } else {
             None
         }
@@ -262,9 +411,27 @@ impl<F: Float, C: FloatChecker<F>> NoisyFloat<F, C> {
         Self::new(F::from(value).unwrap())
     }
 
+    /// Tries to construct a `NoisyFloat` with the given `f32` value.
+    ///
+    /// Returns `None` if the value cannot be represented by `F`
+    /// according to its `NumCast` implementation, or if it is invalid.
+    #[inline]
+    pub fn try_from_f32(value: f32) -> Option<Self> {
+        F::from(value).and_then(Self::try_new)
+    }
+
+    /// Tries to construct a `NoisyFloat` with the given `f64` value.
+    ///
+    /// Returns `None` if the value cannot be represented by `F`
+    /// according to its `NumCast` implementation, or if it is invalid.
+    #[inline]
+    pub fn try_from_f64(value: f64) -> Option<Self> {
+        F::from(value).and_then(Self::try_new)
+    }
+
     /// Returns the underlying float value.
     #[inline]
-    pub fn raw(self) -> F {
+    pub const fn raw(self) -> F {
         self.value
     }
 
@@ -283,71 +450,553 @@ impl<F: Float, C: FloatChecker<F>> NoisyFloat<F, C> {
     pub fn max(self, other: Self) -> Self {
         Ord::max(self, other)
     }
+
+    /// Computes the absolute value.
+    #[inline]
+    pub fn abs(self) -> Self {
+        Self::new(FloatCore::abs(self.value))
+    }
+
+    /// Returns a number that represents the sign of the value.
+    #[inline]
+    pub fn signum(self) -> Self {
+        Self::new(FloatCore::signum(self.value))
+    }
+
+    /// Returns the largest integer less than or equal to the value.
+    #[inline]
+    pub fn floor(self) -> Self {
+        Self::new(FloatCore::floor(self.value))
+    }
+
+    /// Returns the smallest integer greater than or equal to the value.
+    #[inline]
+    pub fn ceil(self) -> Self {
+        Self::new(FloatCore::ceil(self.value))
+    }
+
+    /// Returns the nearest integer to the value, rounding half-way cases away from `0.0`.
+    #[inline]
+    pub fn round(self) -> Self {
+        Self::new(FloatCore::round(self.value))
+    }
+
+    /// Returns the integer part of the value.
+    #[inline]
+    pub fn trunc(self) -> Self {
+        Self::new(FloatCore::trunc(self.value))
+    }
+
+    /// Returns the fractional part of the value.
+    #[inline]
+    pub fn fract(self) -> Self {
+        Self::new(FloatCore::fract(self.value))
+    }
+
+    /// Takes the reciprocal (inverse) of the value, `1/x`.
+    #[inline]
+    pub fn recip(self) -> Self {
+        Self::new(FloatCore::recip(self.value))
+    }
+
+    /// Raises the value to an integer power.
+    #[inline]
+    pub fn powi(self, n: i32) -> Self {
+        Self::new(FloatCore::powi(self.value, n))
+    }
+
+    /// Converts radians to degrees.
+    #[inline]
+    pub fn to_degrees(self) -> Self {
+        Self::new(FloatCore::to_degrees(self.value))
+    }
+
+    /// Converts degrees to radians.
+    #[inline]
+    pub fn to_radians(self) -> Self {
+        Self::new(FloatCore::to_radians(self.value))
+    }
+
+    /// Restricts the value to a certain interval.
+    ///
+    /// Panics if `min > max`.
+    #[inline]
+    pub fn clamp(self, min: Self, max: Self) -> Self {
+        Ord::clamp(self, min, max)
+    }
+
+    /// Linearly interpolates from `start` at `0.0` to `end` at `1.0`,
+    /// using the value as the interpolation parameter.
+    ///
+    /// The result is exact at `0.0` and `1.0`, and does not overflow
+    /// for finite inputs with the parameter between `0.0` and `1.0`.
+    #[inline]
+    pub fn lerp(self, start: Self, end: Self) -> Self {
+        Self::new(lerp(start.value, end.value, self.value))
+    }
+
+    /// Returns the parameter for which `lerp` would give this value,
+    /// i.e. `(self - start) / (end - start)`.
+    ///
+    /// The differences are computed from halved operands when they overflow,
+    /// so the result only overflows if the parameter itself is out of range.
+    ///
+    /// # Panics
+    ///
+    /// If `start == end`, the result is infinite, or NaN if the value is also
+    /// equal to `start`, so this panics through the `FloatChecker` like any
+    /// other operation producing an invalid value.
+    #[inline]
+    pub fn inverse_lerp(self, start: Self, end: Self) -> Self {
+        let offset = self.value - start.value;
+        let width = end.value - start.value;
+        if offset.is_finite() && width.is_finite() {
+            Self::new(offset / width)
+        } else {
+            let two = F::one() + F::one();
+            let offset = self.value / two - start.value / two;
+            let width = end.value / two - start.value / two;
+            Self::new(offset / width)
+        }
+    }
+
+    /// Maps the value linearly from the range `from_start..from_end`
+    /// to the range `to_start..to_end`.
+    ///
+    /// # Panics
+    ///
+    /// Like `inverse_lerp`, this panics through the `FloatChecker` if
+    /// `from_start == from_end`, or if the result is out of range.
+    #[inline]
+    pub fn remap(self, from_start: Self, from_end: Self, to_start: Self, to_end: Self) -> Self {
+        self.inverse_lerp(from_start, from_end)
+            .lerp(to_start, to_end)
+    }
+
+    /// Returns `true` if the value is neither infinite nor NaN.
+    #[inline]
+    pub fn is_finite(self) -> bool {
+        self.value.is_finite()
+    }
+
+    /// Returns `true` if the value is positive or negative infinity.
+    #[inline]
+    pub fn is_infinite(self) -> bool {
+        self.value.is_infinite()
+    }
+
+    /// Returns `true` if the value has a positive sign, including `+0.0` and positive infinity.
+    #[inline]
+    pub fn is_sign_positive(self) -> bool {
+        self.value.is_sign_positive()
+    }
+
+    /// Returns `true` if the value has a negative sign, including `-0.0` and negative infinity.
+    #[inline]
+    pub fn is_sign_negative(self) -> bool {
+        self.value.is_sign_negative()
+    }
 }
 
-impl<F: Float + Default, C: FloatChecker<F>> Default for NoisyFloat<F, C> {
+/// Functions that work on the binary representation of the value.
+impl<F: RawFloat, C: FloatChecker<F>> NoisyFloat<F, C> {
+    /// Returns the raw bit representation of the value.
+    #[inline]
+    pub fn to_bits(self) -> F::Bits {
+        self.value.to_bits()
+    }
+
+    /// Constructs a value from its raw bit representation.
+    ///
+    /// Returns an error if the represented value is rejected by the `FloatChecker`.
+    #[inline]
+    pub fn try_from_bits(bits: F::Bits) -> Result<Self, InvalidValueError> {
+        Self::try_new(F::from_bits(bits)).ok_or(InvalidValueError)
+    }
+
+    /// Returns the smallest representable value greater than `self`.
+    ///
+    /// Both `-0.0` and `+0.0` step to the smallest positive subnormal value.
+    /// Returns an error if that value is rejected by the `FloatChecker`,
+    /// e.g. `R64::MAX.next_up()` fails since infinity is not a valid `R64`.
+    ///
+    /// ```
+    /// use noisy_float::prelude::*;
+    ///
+    /// assert_eq!(r64(1.0).next_up().unwrap(), r64(1.0) + f64::EPSILON);
+    /// assert!(R64::MAX.next_up().is_err());
+    /// assert_eq!(N64::MAX.next_up(), Ok(N64::INFINITY));
+    /// ```
+    #[inline]
+    pub fn next_up(self) -> Result<Self, InvalidValueError> {
+        let bits = self.to_bits();
+        let one = F::Bits::one();
+        let next = if self.value.is_zero() {
+            one
+        } else if self.value.is_sign_positive() {
+            bits + one
+        } else {
+            bits - one
+        };
+        Self::try_from_bits(next)
+    }
+
+    /// Returns the largest representable value less than `self`.
+    ///
+    /// Both `-0.0` and `+0.0` step to the largest negative subnormal value.
+    /// Returns an error if that value is rejected by the `FloatChecker`,
+    /// e.g. `R64::MIN.next_down()` fails since negative infinity is not a valid `R64`.
+    #[inline]
+    pub fn next_down(self) -> Result<Self, InvalidValueError> {
+        let bits = self.to_bits();
+        let one = F::Bits::one();
+        let next = if self.value.is_zero() {
+            sign_mask::<F>() | one
+        } else if self.value.is_sign_positive() {
+            bits - one
+        } else {
+            bits + one
+        };
+        Self::try_from_bits(next)
+    }
+
+    /// Returns the unit in the last place of the value, i.e. the distance
+    /// between the absolute value and the next representable value
+    /// further from zero.
+    ///
+    /// For the largest finite magnitude, where that next value would be
+    /// infinite, this is the distance to the next value towards zero.
+    /// The unit in the last place of an infinite value is infinity.
+    #[inline]
+    pub fn ulp(self) -> Self {
+        let abs = self.value.abs();
+        if abs.is_infinite() {
+            return Self::new(abs);
+        }
+        let bits = abs.to_bits();
+        let next = F::from_bits(bits + F::Bits::one());
+        if next.is_finite() {
+            Self::new(next - abs)
+        } else {
+            Self::new(abs - F::from_bits(bits - F::Bits::one()))
+        }
+    }
+
+    /// Returns the number of representable values one has to step through
+    /// to get from `self` to `other`, in either direction.
+    ///
+    /// `-0.0` and `+0.0` count as a single value, so e.g. the smallest
+    /// negative and positive subnormal values are two steps apart.
+    ///
+    /// ```
+    /// use noisy_float::prelude::*;
+    ///
+    /// assert_eq!(r64(1.0).ulps_between(r64(1.0)), 0);
+    /// assert_eq!(r64(1.0).ulps_between(r64(1.0) + f64::EPSILON), 1);
+    /// assert_eq!(r64(-0.0).ulps_between(r64(0.0)), 0);
+    /// ```
+    #[inline]
+    pub fn ulps_between(self, other: Self) -> F::Bits {
+        let a = self.ordered_bits();
+        let b = other.ordered_bits();
+        if a > b {
+            a - b
+        } else {
+            b - a
+        }
+    }
+
+    /// Maps the value to an unsigned integer with the same ordering,
+    /// where `-0.0` and `+0.0` map to the same integer.
+    #[inline]
+    fn ordered_bits(self) -> F::Bits {
+        let bits = self.to_bits();
+        if self.value.is_sign_negative() {
+            !bits + F::Bits::one()
+        } else {
+            bits | sign_mask::<F>()
+        }
+    }
+
+    /// The inverse of `ordered_bits`, which only accepts its canonical output.
+    #[inline]
+    fn from_ordered_bits(key: F::Bits) -> Result<Self, InvalidValueError> {
+        let sign = sign_mask::<F>();
+        let bits = if key & sign != F::Bits::zero() {
+            key & !sign
+        } else if key.is_zero() {
+            return Err(InvalidValueError);
+        } else {
+            !(key - F::Bits::one())
+        };
+        Self::try_from_bits(bits)
+    }
+}
+
+#[inline]
+fn sign_mask<F: RawFloat>() -> F::Bits {
+    !(!F::Bits::zero() >> 1)
+}
+
+/// Math functions that require the `std` or `libm` feature.
+///
+/// These mirror the methods of `num_traits::Float`, so they can be used
+/// without importing that trait.
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<F: Float + FloatCore, C: FloatChecker<F>> NoisyFloat<F, C> {
+    /// Fused multiply-add. Computes `(self * a) + b` with only one rounding error.
+    #[inline]
+    pub fn mul_add(self, a: Self, b: Self) -> Self {
+        Self::new(Float::mul_add(self.value, a.value, b.value))
+    }
+
+    /// Takes the square root of the value.
+    #[inline]
+    pub fn sqrt(self) -> Self {
+        Self::new(Float::sqrt(self.value))
+    }
+
+    /// Takes the cubic root of the value.
+    #[inline]
+    pub fn cbrt(self) -> Self {
+        Self::new(Float::cbrt(self.value))
+    }
+
+    /// Raises the value to a floating point power.
+    #[inline]
+    pub fn powf(self, n: Self) -> Self {
+        Self::new(Float::powf(self.value, n.value))
+    }
+
+    /// Returns `e^(self)`, (the exponential function).
+    #[inline]
+    pub fn exp(self) -> Self {
+        Self::new(Float::exp(self.value))
+    }
+
+    /// Returns `2^(self)`.
+    #[inline]
+    pub fn exp2(self) -> Self {
+        Self::new(Float::exp2(self.value))
+    }
+
+    /// Returns `e^(self) - 1` in a way that is accurate even if the value is close to zero.
+    #[inline]
+    pub fn exp_m1(self) -> Self {
+        Self::new(Float::exp_m1(self.value))
+    }
+
+    /// Returns the natural logarithm of the value.
+    #[inline]
+    pub fn ln(self) -> Self {
+        Self::new(Float::ln(self.value))
+    }
+
+    /// Returns `ln(1+self)` more accurately than if the operations were performed separately.
+    #[inline]
+    pub fn ln_1p(self) -> Self {
+        Self::new(Float::ln_1p(self.value))
+    }
+
+    /// Returns the logarithm of the value with respect to an arbitrary base.
+    #[inline]
+    pub fn log(self, base: Self) -> Self {
+        Self::new(Float::log(self.value, base.value))
+    }
+
+    /// Returns the base 2 logarithm of the value.
+    #[inline]
+    pub fn log2(self) -> Self {
+        Self::new(Float::log2(self.value))
+    }
+
+    /// Returns the base 10 logarithm of the value.
+    #[inline]
+    pub fn log10(self) -> Self {
+        Self::new(Float::log10(self.value))
+    }
+
+    /// Calculates the length of the hypotenuse of a right-angle triangle given legs of length `self` and `other`.
+    #[inline]
+    pub fn hypot(self, other: Self) -> Self {
+        Self::new(Float::hypot(self.value, other.value))
+    }
+
+    /// Computes the sine of the value (in radians).
+    #[inline]
+    pub fn sin(self) -> Self {
+        Self::new(Float::sin(self.value))
+    }
+
+    /// Computes the cosine of the value (in radians).
+    #[inline]
+    pub fn cos(self) -> Self {
+        Self::new(Float::cos(self.value))
+    }
+
+    /// Computes the tangent of the value (in radians).
+    #[inline]
+    pub fn tan(self) -> Self {
+        Self::new(Float::tan(self.value))
+    }
+
+    /// Computes the arcsine of the value.
+    #[inline]
+    pub fn asin(self) -> Self {
+        Self::new(Float::asin(self.value))
+    }
+
+    /// Computes the arccosine of the value.
+    #[inline]
+    pub fn acos(self) -> Self {
+        Self::new(Float::acos(self.value))
+    }
+
+    /// Computes the arctangent of the value.
+    #[inline]
+    pub fn atan(self) -> Self {
+        Self::new(Float::atan(self.value))
+    }
+
+    /// Computes the four quadrant arctangent of `self` (y) and `other` (x) in radians.
+    #[inline]
+    pub fn atan2(self, other: Self) -> Self {
+        Self::new(Float::atan2(self.value, other.value))
+    }
+
+    /// Hyperbolic sine function.
+    #[inline]
+    pub fn sinh(self) -> Self {
+        Self::new(Float::sinh(self.value))
+    }
+
+    /// Hyperbolic cosine function.
+    #[inline]
+    pub fn cosh(self) -> Self {
+        Self::new(Float::cosh(self.value))
+    }
+
+    /// Hyperbolic tangent function.
+    #[inline]
+    pub fn tanh(self) -> Self {
+        Self::new(Float::tanh(self.value))
+    }
+
+    /// Inverse hyperbolic sine function.
+    #[inline]
+    pub fn asinh(self) -> Self {
+        Self::new(Float::asinh(self.value))
+    }
+
+    /// Inverse hyperbolic cosine function.
+    #[inline]
+    pub fn acosh(self) -> Self {
+        Self::new(Float::acosh(self.value))
+    }
+
+    /// Inverse hyperbolic tangent function.
+    #[inline]
+    pub fn atanh(self) -> Self {
+        Self::new(Float::atanh(self.value))
+    }
+
+    /// Simultaneously computes the sine and cosine of the value.
+    #[inline]
+    pub fn sin_cos(self) -> (Self, Self) {
+        let (sin, cos) = Float::sin_cos(self.value);
+        (Self::new(sin), Self::new(cos))
+    }
+}
+
+impl<F: FloatCore + Default, C: FloatChecker<F>> Default for NoisyFloat<F, C> {
     #[inline]
     fn default() -> Self {
         Self::new(F::default())
     }
 }
 
-impl<F: Float + fmt::Debug, C: FloatChecker<F>> fmt::Debug for NoisyFloat<F, C> {
+impl<F: FloatCore + fmt::Debug, C: FloatChecker<F>> fmt::Debug for NoisyFloat<F, C> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         fmt::Debug::fmt(&self.value, f)
     }
 }
 
-impl<F: Float + fmt::Display, C: FloatChecker<F>> fmt::Display for NoisyFloat<F, C> {
+impl<F: FloatCore + fmt::Display, C: FloatChecker<F>> fmt::Display for NoisyFloat<F, C> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         fmt::Display::fmt(&self.value, f)
     }
 }
 
-impl<F: Float + fmt::LowerExp, C: FloatChecker<F>> fmt::LowerExp for NoisyFloat<F, C> {
+impl<F: FloatCore + fmt::LowerExp, C: FloatChecker<F>> fmt::LowerExp for NoisyFloat<F, C> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         fmt::LowerExp::fmt(&self.value, f)
     }
 }
 
-impl<F: Float + fmt::UpperExp, C: FloatChecker<F>> fmt::UpperExp for NoisyFloat<F, C> {
+impl<F: FloatCore + fmt::UpperExp, C: FloatChecker<F>> fmt::UpperExp for NoisyFloat<F, C> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         fmt::UpperExp::fmt(&self.value, f)
     }
 }
 
+/// Returns whether values of the float type are serialized as `f32`.
+///
+/// Without the `half` feature, every float type serializes as itself.
+#[cfg(all(feature = "serde-1", not(feature = "half")))]
+#[allow(clippy::extra_unused_type_parameters)]
+#[inline]
+fn serde_widens<F: 'static>() -> bool {
+    false
+}
+
+#[cfg(all(feature = "serde-1", feature = "half"))]
+use half_impl::serde_widens;
+
 #[cfg(feature = "serde-1")]
-impl<F: Float + Serialize, C: FloatChecker<F>> Serialize for NoisyFloat<F, C> {
+impl<F: FloatCore + Serialize + 'static, C: FloatChecker<F>> Serialize for NoisyFloat<F, C> {
     fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        if serde_widens::<F>() {
+            if let Some(value) = num_traits::ToPrimitive::to_f32(&self.value) {
+                return value.serialize(ser);
+            }
+        }
         self.value.serialize(ser)
     }
 }
 
 #[cfg(feature = "serde-1")]
-impl<'de, F: Float + Deserialize<'de>, C: FloatChecker<F>> Deserialize<'de> for NoisyFloat<F, C> {
+impl<'de, F: FloatCore + Deserialize<'de> + 'static, C: FloatChecker<F>> Deserialize<'de>
+    for NoisyFloat<F, C>
+{
     fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        if serde_widens::<F>() {
+            // narrow the value like `TryFrom`, rejecting overflow and invalid values
+            let wide = f32::deserialize(de)?;
+            let value = <F as num_traits::NumCast>::from(wide)
+                .filter(|value| !(wide.is_finite() && value.is_infinite()))
+                .and_then(Self::try_new);
+            return value.ok_or_else(|| serde::de::Error::custom(InvalidValueError));
+        }
         let value = F::deserialize(de)?;
         Ok(Self::new(value))
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::approx_constant)]
 mod tests {
     extern crate std;
-    use std::prelude::v1::*;
+    use std::vec::Vec;
 
     use crate::prelude::*;
     #[cfg(feature = "serde-1")]
     use serde_derive::{Deserialize, Serialize};
-    #[cfg(feature = "serde-1")]
-    use serde_json;
     use std::{
-        f32,
-        f64::{self, consts},
+        f32, f64,
         hash::{Hash, Hasher},
         mem::{align_of, size_of},
     };
@@ -360,14 +1009,16 @@ mod tests {
         let mut value = n64(18.0);
         value %= n64(5.0);
         assert_eq!(-value, n64(-3.0));
-        assert_eq!(r64(1.0).exp(), consts::E);
+        #[cfg(any(feature = "std", feature = "libm"))]
+        assert_eq!(r64(1.0).exp(), f64::consts::E);
         assert_eq!((N64::try_new(1.0).unwrap() / N64::infinity()), 0.0);
         assert_eq!(N64::from_f32(f32::INFINITY), N64::from_f64(f64::INFINITY));
         assert_eq!(R64::try_new(f64::NEG_INFINITY), None);
         assert_eq!(N64::try_new(f64::NAN), None);
         assert_eq!(R64::try_new(f64::NAN), None);
         assert_eq!(N64::try_borrowed(&f64::NAN), None);
-        assert_eq!(N64::try_borrowed_mut(&mut f64::NAN), None);
+        let mut nan = f64::NAN;
+        assert_eq!(N64::try_borrowed_mut(&mut nan), None);
     }
 
     #[test]
@@ -420,12 +1071,110 @@ mod tests {
         assert_eq!(r64(1.0).max(r64(3.0)), r64(3.0));
     }
 
+    // Deliberately does not import `num_traits::Float`.
+    mod inherent {
+        use crate::types::{n32, r64, N32};
+
+        #[test]
+        fn core_methods() {
+            assert_eq!(r64(-2.5).abs(), r64(2.5));
+            assert_eq!(r64(-2.5).floor(), r64(-3.0));
+            assert_eq!(r64(-2.5).ceil(), r64(-2.0));
+            assert_eq!(r64(-2.5).round(), r64(-3.0));
+            assert_eq!(r64(3.0).powi(3), r64(27.0));
+            assert_eq!(r64(4.0).recip(), r64(0.25));
+            assert_eq!(r64(5.0).clamp(r64(0.0), r64(1.0)), r64(1.0));
+            assert!(n32(-0.0).is_sign_negative());
+            assert!(!N32::new(f32::INFINITY).is_finite());
+        }
+
+        #[cfg(any(feature = "std", feature = "libm"))]
+        #[test]
+        fn float_methods() {
+            assert_eq!(r64(9.0).sqrt(), r64(3.0));
+            assert_eq!(r64(2.0).powf(r64(10.0)), r64(1024.0));
+            assert_eq!(r64(0.0).exp(), r64(1.0));
+            assert_eq!(r64(1.0).ln(), r64(0.0));
+            assert_eq!(r64(0.0).sin(), r64(0.0));
+            assert_eq!(r64(0.0).cos(), r64(1.0));
+            assert_eq!(r64(3.0).hypot(r64(4.0)), r64(5.0));
+            assert_eq!(r64(1.0).atan2(r64(1.0)), r64(core::f64::consts::FRAC_PI_4));
+            assert_eq!(r64(2.0).mul_add(r64(3.0), r64(4.0)), r64(10.0));
+        }
+    }
+
+    #[test]
+    fn consts() {
+        const HALF: R64 = R64::const_new(0.5);
+        const UNCHECKED: N32 = N32::new_unchecked(-1.0);
+        const RAW: f64 = R64::PI.raw();
+
+        assert_eq!(HALF + HALF, R64::ONE);
+        assert_eq!(UNCHECKED, n32(-1.0));
+        assert_eq!(RAW, f64::consts::PI);
+        assert_eq!(R64::ZERO, 0.0);
+        assert_eq!(R32::E, f32::consts::E);
+        assert_eq!(R64::EPSILON, f64::EPSILON);
+        assert_eq!(R64::MAX, f64::MAX);
+        assert_eq!(R32::MIN, f32::MIN);
+        assert_eq!(N64::MIN_POSITIVE, f64::MIN_POSITIVE);
+        assert_eq!(N64::INFINITY, f64::INFINITY);
+        assert_eq!(N32::NEG_INFINITY, f32::NEG_INFINITY);
+    }
+
+    #[test]
+    fn literal_macros() {
+        assert_eq!(r64!(1.5), r64(1.5));
+        assert_eq!(r32!(-2.0), r32(-2.0));
+        assert_eq!(n64!(f64::INFINITY), N64::INFINITY);
+        assert_eq!(n32!(f32::MIN_POSITIVE), N32::MIN_POSITIVE);
+        assert_eq!(r64!(2.0 * 3.0), 6.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn const_new_at_runtime() {
+        let _ = N64::const_new(f64::NAN);
+    }
+
     #[test]
     fn epsilon() {
         assert_eq!(R32::epsilon(), f32::EPSILON);
         assert_eq!(R64::epsilon(), f64::EPSILON);
     }
 
+    #[cfg(feature = "std")]
+    #[test]
+    fn std_error() {
+        use crate::InvalidValueError;
+        use std::{boxed::Box, convert::TryFrom, error::Error, string::ToString};
+
+        fn parse(value: f64) -> Result<R64, Box<dyn Error>> {
+            Ok(R64::try_from(value)?)
+        }
+
+        assert_eq!(parse(1.5).unwrap(), r64(1.5));
+        let err = parse(f64::INFINITY).unwrap_err();
+        assert!(err.is::<InvalidValueError>());
+        assert_eq!(err.to_string(), "illegal value");
+    }
+
+    #[test]
+    fn float_core() {
+        use num_traits::float::FloatCore;
+
+        fn abs_max<T: FloatCore>(a: T, b: T) -> T {
+            FloatCore::max(FloatCore::abs(a), FloatCore::abs(b))
+        }
+
+        assert_eq!(abs_max(r64(-3.0), r64(2.0)), r64(3.0));
+        assert_eq!(
+            abs_max(n32(1.0), <N32 as FloatCore>::neg_infinity()),
+            <N32 as FloatCore>::infinity()
+        );
+        assert_eq!(<R32 as FloatCore>::epsilon(), f32::EPSILON);
+    }
+
     #[test]
     fn test_try_into() {
         use std::convert::{TryFrom, TryInto};
@@ -475,6 +1224,137 @@ mod tests {
         assert_eq!(hash_bytes(r32(-0.0)), hash_bytes(0.0f32.to_bits()));
     }
 
+    #[test]
+    fn raw_float() {
+        use crate::RawFloat;
+
+        assert_eq!(RawFloat::to_bits(1.5f32), 1.5f32.to_bits());
+        assert_eq!(<f64 as RawFloat>::from_bits(2.5f64.to_bits()), 2.5);
+        assert_eq!(
+            hash_bytes(OptR64::some(r64(-0.0))),
+            hash_bytes(Some(r64(0.0)))
+        );
+    }
+
+    #[test]
+    fn ord_matches_std_defaults() {
+        use std::cmp::Ordering;
+
+        let (neg_zero, zero) = (r64(-0.0), r64(0.0));
+        assert_eq!(neg_zero.cmp(&zero), Ordering::Equal);
+        assert_eq!(r64(1.0).cmp(&r64(2.0)), Ordering::Less);
+        assert_eq!(r64(2.0).cmp(&r64(1.0)), Ordering::Greater);
+        // like the default implementations, ties resolve to `self` for `min`
+        // and to `other` for `max`
+        assert!(Ord::min(neg_zero, zero).is_sign_negative());
+        assert!(Ord::max(neg_zero, zero).is_sign_positive());
+        assert!(Ord::min(zero, neg_zero).is_sign_positive());
+        assert!(Ord::max(zero, neg_zero).is_sign_negative());
+        assert!(neg_zero.clamp(zero, r64(1.0)).is_sign_negative());
+        assert_eq!(r64(5.0).clamp(r64(-1.0), r64(1.0)), r64(1.0));
+        assert_eq!(r64(-5.0).clamp(r64(-1.0), r64(1.0)), r64(-1.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn clamp_inverted_bounds() {
+        r64(0.0).clamp(r64(1.0), r64(-1.0));
+    }
+
+    #[test]
+    fn interpolation() {
+        assert_eq!(r64(0.25).lerp(r64(2.0), r64(4.0)), r64(2.5));
+        assert_eq!(r64(0.0).lerp(r64(0.1), r64(0.7)), r64(0.1));
+        assert_eq!(r64(1.0).lerp(r64(0.1), r64(0.7)), r64(0.7));
+        assert_eq!(r64(0.5).lerp(r64(-f64::MAX), r64(f64::MAX)), r64(0.0));
+        assert_eq!(r64(-1.0).lerp(r64(2.0), r64(4.0)), r64(0.0));
+        assert_eq!(n64(0.0).lerp(n64(1.0), N64::INFINITY), n64(1.0));
+        assert_eq!(n64(1.0).lerp(N64::NEG_INFINITY, n64(1.0)), n64(1.0));
+        assert_eq!(r64(2.5).inverse_lerp(r64(2.0), r64(4.0)), r64(0.25));
+        assert_eq!(n64(1.0).inverse_lerp(n64(2.0), n64(2.0)), N64::NEG_INFINITY);
+        assert_eq!(R64::MAX.inverse_lerp(R64::MIN, R64::MAX), r64(1.0));
+        assert_eq!(R64::MAX.inverse_lerp(R64::MIN, r64(0.0)), r64(2.0));
+        assert_eq!(
+            r32(0.0).remap(R32::MIN, R32::MAX, r32(-1.0), r32(1.0)),
+            r32(0.0)
+        );
+        assert_eq!(
+            N64::INFINITY.inverse_lerp(N64::MIN, N64::MAX),
+            N64::INFINITY
+        );
+        assert_eq!(
+            r32(15.0).remap(r32(10.0), r32(20.0), r32(0.0), r32(-100.0)),
+            r32(-50.0)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn inverse_lerp_empty_range() {
+        r64(1.0).inverse_lerp(r64(2.0), r64(2.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn inverse_lerp_empty_range_at_start() {
+        n64(2.0).inverse_lerp(n64(2.0), n64(2.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn remap_empty_range() {
+        r32(1.0).remap(r32(2.0), r32(2.0), r32(0.0), r32(1.0));
+    }
+
+    #[test]
+    fn bits() {
+        use crate::InvalidValueError;
+
+        assert_eq!(r64(1.5).to_bits(), 1.5f64.to_bits());
+        assert_eq!(R32::try_from_bits(2.5f32.to_bits()), Ok(r32(2.5)));
+        assert_eq!(
+            R64::try_from_bits(f64::INFINITY.to_bits()),
+            Err(InvalidValueError)
+        );
+        assert_eq!(
+            N64::try_from_bits(f64::INFINITY.to_bits()),
+            Ok(N64::INFINITY)
+        );
+        assert_eq!(
+            N32::try_from_bits(f32::NAN.to_bits()),
+            Err(InvalidValueError)
+        );
+
+        let tiny = f64::from_bits(1);
+        assert_eq!(r64(0.0).next_up(), Ok(r64(tiny)));
+        assert_eq!(r64(-0.0).next_up(), Ok(r64(tiny)));
+        assert_eq!(r64(0.0).next_down(), Ok(r64(-tiny)));
+        assert_eq!(r64(-tiny).next_up().map(R64::is_sign_negative), Ok(true));
+        assert_eq!(r64(1.0).next_up(), Ok(r64(1.0 + f64::EPSILON)));
+        assert_eq!(r64(1.0).next_up().and_then(R64::next_down), Ok(r64(1.0)));
+        assert_eq!(r64(-1.0).next_down(), Ok(r64(-1.0 - f64::EPSILON)));
+        assert_eq!(R64::MAX.next_up(), Err(InvalidValueError));
+        assert_eq!(R64::MIN.next_down(), Err(InvalidValueError));
+        assert_eq!(N64::MAX.next_up(), Ok(N64::INFINITY));
+        assert_eq!(N64::INFINITY.next_up(), Err(InvalidValueError));
+        assert_eq!(N64::NEG_INFINITY.next_down(), Err(InvalidValueError));
+        assert_eq!(N64::NEG_INFINITY.next_up(), Ok(-N64::MAX));
+
+        assert_eq!(r64(1.0).ulp(), R64::EPSILON);
+        assert_eq!(r64(-1.0).ulp(), R64::EPSILON);
+        assert_eq!(r64(0.0).ulp(), r64(tiny));
+        assert_eq!(R32::MAX.ulp(), R32::MAX - R32::MAX.next_down().unwrap());
+        assert_eq!(N32::NEG_INFINITY.ulp(), N32::INFINITY);
+
+        assert_eq!(r64(1.0).ulps_between(r64(1.0)), 0);
+        assert_eq!(r64(1.0).ulps_between(r64(1.0 + 2.0 * f64::EPSILON)), 2);
+        assert_eq!(r64(-0.0).ulps_between(r64(0.0)), 0);
+        assert_eq!(r64(-tiny).ulps_between(r64(tiny)), 2);
+        assert_eq!(r64(tiny).ulps_between(r64(-tiny)), 2);
+        assert_eq!(r32(-1.0).ulps_between(r32(1.0)), 2 * 1.0f32.to_bits());
+        assert_eq!(N64::MAX.ulps_between(N64::INFINITY), 1);
+    }
+
     #[cfg(feature = "serde-1")]
     #[test]
     fn serialize_transparently_as_float() {
@@ -0,0 +1,444 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lookup tables interpolating between `NoisyFloat` values.
+//!
+//! ```
+//! use noisy_float::{lookup::{Extrapolation, LookupTable}, prelude::*};
+//!
+//! let table = LookupTable::new(
+//!     vec![r64(0.0), r64(10.0), r64(20.0)],
+//!     vec![r64(1.0), r64(2.0), r64(0.0)],
+//! )
+//! .unwrap();
+//! assert_eq!(table.eval(r64(5.0)), r64(1.5));
+//! assert_eq!(table.eval(r64(30.0)), r64(0.0));
+//!
+//! let table = table.with_extrapolation(Extrapolation::Extrapolate);
+//! assert_eq!(table.eval(r64(30.0)), r64(-2.0));
+//! ```
+//!
+//! This module requires the `std` feature.
+
+use crate::{lerp, FloatChecker, NoisyFloat};
+use core::fmt;
+use num_traits::float::FloatCore;
+use std::vec::Vec;
+
+/// The error returned when constructing a `LookupTable` fails.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LookupTableError {
+    /// The numbers of knots and values are different.
+    LengthMismatch,
+    /// Fewer than two knots were given.
+    TooFewKnots,
+    /// The knots are not strictly increasing.
+    UnsortedKnots {
+        /// The index of the first knot that is not larger than the previous one.
+        index: usize,
+    },
+    /// A knot or a value is infinite.
+    NonFinite {
+        /// The index of the first infinite knot or value.
+        index: usize,
+    },
+    /// The distance between two adjacent knots is not finite.
+    KnotSpacing {
+        /// The index of the first knot whose distance from the previous one
+        /// overflows.
+        index: usize,
+    },
+    /// The slope between two adjacent knots is infinite, because their
+    /// values are too far apart for the distance between the knots.
+    InfiniteSlope {
+        /// The index of the first knot whose slope from the previous one
+        /// overflows.
+        index: usize,
+    },
+}
+
+impl fmt::Display for LookupTableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            LookupTableError::LengthMismatch => {
+                write!(f, "lookup table has different numbers of knots and values")
+            }
+            LookupTableError::TooFewKnots => write!(f, "lookup table with fewer than two knots"),
+            LookupTableError::UnsortedKnots { index } => {
+                write!(f, "lookup table knot at index {} is not increasing", index)
+            }
+            LookupTableError::NonFinite { index } => {
+                write!(f, "lookup table entry at index {} is infinite", index)
+            }
+            LookupTableError::KnotSpacing { index } => write!(
+                f,
+                "lookup table knot at index {} is too far from the previous one",
+                index
+            ),
+            LookupTableError::InfiniteSlope { index } => write!(
+                f,
+                "lookup table slope before the knot at index {} is infinite",
+                index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LookupTableError {}
+
+/// How a `LookupTable` interpolates between its knots.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Interpolation {
+    /// The value of the nearest knot, or of the lower one when
+    /// the input is exactly between two knots.
+    Nearest,
+    /// Linear interpolation between the surrounding knots.
+    Linear,
+    /// Monotone piecewise cubic Hermite interpolation, as by Fritsch and
+    /// Carlson, which is smooth but does not overshoot the values.
+    Cubic,
+}
+
+/// How a `LookupTable` handles inputs outside of the range of its knots.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Extrapolation {
+    /// The value at the nearest end of the table.
+    Clamp,
+    /// Extends the first or last segment of the table linearly,
+    /// or clamps with `Interpolation::Nearest`.
+    Extrapolate,
+}
+
+/// A function given by its values at strictly increasing knots,
+/// interpolated between them.
+///
+/// The knots and values are validated when the table is constructed.
+/// By default, the table interpolates linearly and clamps inputs to the
+/// range of the knots. The segment holding an input is found by binary
+/// search, relying on the total order of `NoisyFloat`.
+pub struct LookupTable<F: FloatCore, C: FloatChecker<F>> {
+    knots: Vec<NoisyFloat<F, C>>,
+    values: Vec<NoisyFloat<F, C>>,
+    interpolation: Interpolation,
+    extrapolation: Extrapolation,
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> LookupTable<F, C> {
+    /// Constructs a table with the given values at the given knots.
+    ///
+    /// Returns an error if the numbers of knots and values are different,
+    /// if there are fewer than two knots, if the knots are not strictly
+    /// increasing, if a knot or value is infinite, if the distance between
+    /// two adjacent knots overflows, or if the slope between them does.
+    pub fn new(
+        knots: Vec<NoisyFloat<F, C>>,
+        values: Vec<NoisyFloat<F, C>>,
+    ) -> Result<Self, LookupTableError> {
+        if knots.len() != values.len() {
+            return Err(LookupTableError::LengthMismatch);
+        }
+        if knots.len() < 2 {
+            return Err(LookupTableError::TooFewKnots);
+        }
+        if let Some(index) = knots
+            .iter()
+            .zip(&values)
+            .position(|(knot, value)| !knot.is_finite() || !value.is_finite())
+        {
+            return Err(LookupTableError::NonFinite { index });
+        }
+        if let Some(index) = knots.windows(2).position(|pair| pair[0] >= pair[1]) {
+            return Err(LookupTableError::UnsortedKnots { index: index + 1 });
+        }
+        if let Some(index) = knots
+            .windows(2)
+            .position(|pair| !(pair[1].raw() - pair[0].raw()).is_finite())
+        {
+            return Err(LookupTableError::KnotSpacing { index: index + 1 });
+        }
+        let table = LookupTable {
+            knots,
+            values,
+            interpolation: Interpolation::Linear,
+            extrapolation: Extrapolation::Clamp,
+        };
+        if let Some(index) = (0..table.knots.len() - 1).position(|i| !table.secant(i).is_finite()) {
+            return Err(LookupTableError::InfiniteSlope { index: index + 1 });
+        }
+        Ok(table)
+    }
+
+    /// Sets how the table interpolates between its knots.
+    #[inline]
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Sets how the table handles inputs outside of the range of its knots.
+    #[inline]
+    pub fn with_extrapolation(mut self, extrapolation: Extrapolation) -> Self {
+        self.extrapolation = extrapolation;
+        self
+    }
+
+    /// Returns the knots of the table.
+    #[inline]
+    pub fn knots(&self) -> &[NoisyFloat<F, C>] {
+        &self.knots
+    }
+
+    /// Returns the values of the table at its knots.
+    #[inline]
+    pub fn values(&self) -> &[NoisyFloat<F, C>] {
+        &self.values
+    }
+
+    /// Returns the interpolated value at `x`.
+    ///
+    /// Uses the `FloatChecker` to assert that the result is valid,
+    /// which may fail when extrapolating far outside of the table.
+    pub fn eval(&self, x: NoisyFloat<F, C>) -> NoisyFloat<F, C> {
+        let last = self.knots.len() - 1;
+        if x <= self.knots[0] || x >= self.knots[last] {
+            let (end, next) = if x <= self.knots[0] {
+                (0, 1)
+            } else {
+                (last, last - 1)
+            };
+            if x == self.knots[end]
+                || self.extrapolation == Extrapolation::Clamp
+                || self.interpolation == Interpolation::Nearest
+            {
+                return self.values[end];
+            }
+            let slope = self.secant(end.min(next));
+            if slope == F::zero() {
+                return self.values[end];
+            }
+            let offset = x.raw() - self.knots[end].raw();
+            return NoisyFloat::new(self.values[end].raw() + slope * offset);
+        }
+
+        // the segment `knots[i]..knots[i + 1]` holds `x`
+        let i = self.knots.partition_point(|&knot| knot <= x) - 1;
+        let (x0, x1) = (self.knots[i].raw(), self.knots[i + 1].raw());
+        let (y0, y1) = (self.values[i].raw(), self.values[i + 1].raw());
+        let width = x1 - x0;
+        let t = (x.raw() - x0) / width;
+        let one = F::one();
+        let two = one + one;
+        let three = two + one;
+        match self.interpolation {
+            Interpolation::Nearest if t <= one / two => self.values[i],
+            Interpolation::Nearest => self.values[i + 1],
+            Interpolation::Linear => NoisyFloat::new(lerp(y0, y1, t)),
+            Interpolation::Cubic => {
+                let (m0, m1) = (self.tangent(i), self.tangent(i + 1));
+                let t2 = t * t;
+                let t3 = t2 * t;
+                let h00 = two * t3 - three * t2 + one;
+                let h10 = t3 - two * t2 + t;
+                let h01 = three * t2 - two * t3;
+                let h11 = t3 - t2;
+                NoisyFloat::new(h00 * y0 + h10 * width * m0 + h01 * y1 + h11 * width * m1)
+            }
+        }
+    }
+
+    /// The slope of the segment `knots[i]..knots[i + 1]`.
+    #[inline]
+    fn secant(&self, i: usize) -> F {
+        (self.values[i + 1].raw() - self.values[i].raw())
+            / (self.knots[i + 1].raw() - self.knots[i].raw())
+    }
+
+    /// The slope of the cubic interpolation at `knots[i]`, which is zero
+    /// at local extrema so that the interpolation stays monotone.
+    fn tangent(&self, i: usize) -> F {
+        let last = self.knots.len() - 1;
+        if i == 0 {
+            return self.secant(0);
+        } else if i == last {
+            return self.secant(last - 1);
+        }
+        let (before, after) = (self.secant(i - 1), self.secant(i));
+        if before == F::zero() || after == F::zero() || (before < F::zero()) != (after < F::zero())
+        {
+            return F::zero();
+        }
+        let h0 = self.knots[i].raw() - self.knots[i - 1].raw();
+        let h1 = self.knots[i + 1].raw() - self.knots[i].raw();
+        let w0 = h0 + h1 + h1;
+        let w1 = h0 + h0 + h1;
+        (w0 + w1) / (w0 / before + w1 / after)
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Clone for LookupTable<F, C> {
+    fn clone(&self) -> Self {
+        LookupTable {
+            knots: self.knots.clone(),
+            values: self.values.clone(),
+            interpolation: self.interpolation,
+            extrapolation: self.extrapolation,
+        }
+    }
+}
+
+impl<F: FloatCore + fmt::Debug, C: FloatChecker<F>> fmt::Debug for LookupTable<F, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("LookupTable")
+            .field("knots", &self.knots)
+            .field("values", &self.values)
+            .field("interpolation", &self.interpolation)
+            .field("extrapolation", &self.extrapolation)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        checkers::{FiniteChecker, NumChecker},
+        prelude::*,
+    };
+
+    fn new_table(knots: &[f64], values: &[f64]) -> LookupTable<f64, FiniteChecker> {
+        LookupTable::new(
+            knots.iter().map(|&x| r64(x)).collect(),
+            values.iter().map(|&y| r64(y)).collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn validation() {
+        let new = |knots: &[f64], values: &[f64]| {
+            LookupTable::<f64, NumChecker>::new(
+                knots.iter().map(|&x| n64(x)).collect(),
+                values.iter().map(|&y| n64(y)).collect(),
+            )
+            .unwrap_err()
+        };
+        assert_eq!(new(&[0.0, 1.0], &[0.0]), LookupTableError::LengthMismatch);
+        assert_eq!(new(&[0.0], &[0.0]), LookupTableError::TooFewKnots);
+        assert_eq!(
+            new(&[0.0, 1.0, 0.5], &[0.0; 3]),
+            LookupTableError::UnsortedKnots { index: 2 }
+        );
+        assert_eq!(
+            new(&[0.0, f64::INFINITY], &[0.0; 2]),
+            LookupTableError::NonFinite { index: 1 }
+        );
+        assert_eq!(
+            new(&[-f64::MAX, 1e300], &[0.0; 2]),
+            LookupTableError::KnotSpacing { index: 1 }
+        );
+        assert_eq!(
+            std::format!("{}", new(&[-f64::MAX, -1e308, 1e308], &[0.0; 3])),
+            "lookup table knot at index 2 is too far from the previous one"
+        );
+        assert_eq!(
+            new(&[0.0, 1.0, 2.0], &[0.0, -f64::MAX, f64::MAX]),
+            LookupTableError::InfiniteSlope { index: 2 }
+        );
+        assert_eq!(
+            new(&[0.0, 1e-300], &[0.0, 1e10]),
+            LookupTableError::InfiniteSlope { index: 1 }
+        );
+    }
+
+    #[test]
+    fn linear() {
+        let table = new_table(&[-1.0, 0.0, 2.0], &[3.0, 1.0, 2.0]);
+        assert_eq!(table.eval(r64(-1.0)), r64(3.0));
+        assert_eq!(table.eval(r64(-0.5)), r64(2.0));
+        assert_eq!(table.eval(r64(0.0)), r64(1.0));
+        assert_eq!(table.eval(r64(1.0)), r64(1.5));
+        assert_eq!(table.eval(r64(2.0)), r64(2.0));
+        assert_eq!(table.eval(r64(-5.0)), r64(3.0));
+        assert_eq!(table.eval(r64(5.0)), r64(2.0));
+
+        let table = table.with_extrapolation(Extrapolation::Extrapolate);
+        assert_eq!(table.eval(r64(-2.0)), r64(5.0));
+        assert_eq!(table.eval(r64(4.0)), r64(3.0));
+
+        // knots far apart, but not too far
+        let table = new_table(&[-f64::MAX, 0.0, f64::MAX], &[0.0, 1.0, 2.0]);
+        assert_eq!(table.eval(r64(-f64::MAX / 2.0)), r64(0.5));
+        assert_eq!(table.eval(r64(f64::MAX / 4.0)), r64(1.25));
+    }
+
+    #[test]
+    fn nearest() {
+        let table = new_table(&[0.0, 1.0, 3.0], &[10.0, 20.0, 30.0])
+            .with_interpolation(Interpolation::Nearest)
+            .with_extrapolation(Extrapolation::Extrapolate);
+        assert_eq!(table.eval(r64(0.4)), r64(10.0));
+        assert_eq!(table.eval(r64(0.5)), r64(10.0));
+        assert_eq!(table.eval(r64(0.6)), r64(20.0));
+        assert_eq!(table.eval(r64(2.5)), r64(30.0));
+        assert_eq!(table.eval(r64(-1.0)), r64(10.0));
+        assert_eq!(table.eval(r64(9.0)), r64(30.0));
+    }
+
+    #[test]
+    fn cubic() {
+        let knots = [0.0, 1.0, 2.0, 4.0, 5.0];
+        let values = [0.0, 1.0, 1.0, 3.0, 10.0];
+        let table = new_table(&knots, &values).with_interpolation(Interpolation::Cubic);
+        for (&x, &y) in knots.iter().zip(&values) {
+            assert_eq!(table.eval(r64(x)), r64(y));
+        }
+        // monotone data gives a monotone interpolation without overshoot
+        let mut previous = table.eval(r64(0.0));
+        for i in 1..=500 {
+            let value = table.eval(r64(i as f64 / 100.0));
+            assert!(value >= previous);
+            previous = value;
+        }
+        assert_eq!(table.eval(r64(1.5)), r64(1.0));
+
+        // linear data is reproduced exactly
+        let table =
+            new_table(&[0.0, 1.0, 3.0], &[0.0, 2.0, 6.0]).with_interpolation(Interpolation::Cubic);
+        assert!((table.eval(r64(2.0)) - 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn extreme_values() {
+        let new = |values: &[f64]| {
+            LookupTable::new(
+                [r64(0.0), r64(1.0)].to_vec(),
+                values.iter().map(|&y| r64(y)).collect(),
+            )
+        };
+        assert_eq!(
+            new(&[-f64::MAX, f64::MAX]).unwrap_err(),
+            LookupTableError::InfiniteSlope { index: 1 }
+        );
+
+        let half = f64::MAX / 2.0;
+        let table = new(&[-half, half])
+            .unwrap()
+            .with_extrapolation(Extrapolation::Extrapolate);
+        assert_eq!(table.eval(r64(0.5)), r64(0.0));
+        assert_eq!(table.eval(r64(1.25)), r64(1.5 * half));
+        let table = table.with_interpolation(Interpolation::Cubic);
+        assert_eq!(table.eval(r64(0.5)), r64(0.0));
+        assert_eq!(table.eval(r64(-0.25)), r64(-1.5 * half));
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{FloatChecker, NoisyFloat};
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+use num_traits::float::FloatCore;
+#[cfg(feature = "serde-1")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// An optional `NoisyFloat` that takes up no more space than the underlying float.
+///
+/// `Option<NoisyFloat<F, C>>` needs extra space for its discriminant.
+/// Since NaN is never a valid `NoisyFloat` value, this type instead stores
+/// `None` as NaN, so that e.g. `OptR64` has the same size as `f64`.
+///
+/// Typical users will not need to access this struct directly, but
+/// can instead use the type aliases found in the module `noisy_float::types`.
+///
+/// Any NaN bit pattern is read back as `None`, which allows reinterpreting
+/// a slice of raw floats where NaN marks a missing value without copying,
+/// using `from_raw_slice` or `try_from_raw_slice`.
+///
+/// ```
+/// use noisy_float::prelude::*;
+///
+/// let samples = [1.5, f64::NAN, -3.0];
+/// let samples = OptR64::try_from_raw_slice(&samples).unwrap();
+/// assert_eq!(samples[0].get(), Some(r64(1.5)));
+/// assert_eq!(samples[1].get(), None);
+/// assert_eq!(std::mem::size_of::<OptR64>(), std::mem::size_of::<f64>());
+/// ```
+#[repr(transparent)]
+pub struct OptionalFloat<F: FloatCore, C: FloatChecker<F>> {
+    value: F,
+    checker: PhantomData<C>,
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> OptionalFloat<F, C> {
+    #[inline]
+    fn new_unchecked(value: F) -> Self {
+        OptionalFloat {
+            value,
+            checker: PhantomData,
+        }
+    }
+
+    /// Constructs an `OptionalFloat` holding no value.
+    #[inline]
+    pub fn none() -> Self {
+        Self::new_unchecked(F::nan())
+    }
+
+    /// Constructs an `OptionalFloat` holding the given value.
+    #[inline]
+    pub fn some(value: NoisyFloat<F, C>) -> Self {
+        Self::new_unchecked(value.raw())
+    }
+
+    /// Constructs an `OptionalFloat` from the raw float representation,
+    /// where NaN stands for `None`.
+    ///
+    /// Uses the `FloatChecker` to assert that any other value is valid.
+    #[inline]
+    pub fn from_raw(value: F) -> Self {
+        if !value.is_nan() {
+            C::assert(value);
+        }
+        Self::new_unchecked(value)
+    }
+
+    /// Tries to construct an `OptionalFloat` from the raw float representation,
+    /// where NaN stands for `None`.
+    ///
+    /// Returns `None` if the value is neither NaN nor valid.
+    #[inline]
+    pub fn try_from_raw(value: F) -> Option<Self> {
+        if Self::is_valid_raw(value) {
+            Some(Self::new_unchecked(value))
+        } else {
+            None
+        }
+    }
+
+    /// Converts a slice of raw floats in-place to a slice of `OptionalFloat`s,
+    /// where NaN stands for `None`.
+    ///
+    /// Uses the `FloatChecker` to assert that all other values are valid.
+    #[inline]
+    pub fn from_raw_slice(values: &[F]) -> &[Self] {
+        for &value in values {
+            if !value.is_nan() {
+                C::assert(value);
+            }
+        }
+        Self::unchecked_from_raw_slice(values)
+    }
+
+    /// Tries to convert a slice of raw floats in-place to a slice of `OptionalFloat`s,
+    /// where NaN stands for `None`.
+    ///
+    /// Returns `None` if any value is neither NaN nor valid.
+    #[inline]
+    pub fn try_from_raw_slice(values: &[F]) -> Option<&[Self]> {
+        if values.iter().all(|&value| Self::is_valid_raw(value)) {
+            Some(Self::unchecked_from_raw_slice(values))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn unchecked_from_raw_slice(values: &[F]) -> &[Self] {
+        // This is safe because `OptionalFloat` is a thin wrapper around the
+        // floating-point type.
+        unsafe { &*(values as *const [F] as *const [Self]) }
+    }
+
+    /// Converts a mutable slice of raw floats in-place to a mutable slice of
+    /// `OptionalFloat`s, where NaN stands for `None`.
+    ///
+    /// Returns `None` if any value is neither NaN nor valid.
+    #[inline]
+    pub fn try_from_raw_slice_mut(values: &mut [F]) -> Option<&mut [Self]> {
+        if values.iter().all(|&value| Self::is_valid_raw(value)) {
+            // This is safe because `OptionalFloat` is a thin wrapper around the
+            // floating-point type.
+            Some(unsafe { &mut *(values as *mut [F] as *mut [Self]) })
+        } else {
+            None
+        }
+    }
+
+    /// Converts a slice of `OptionalFloat`s in-place to a slice of raw floats,
+    /// where `None` is represented by NaN.
+    #[inline]
+    pub fn as_raw_slice(values: &[Self]) -> &[F] {
+        // This is safe because `OptionalFloat` is a thin wrapper around the
+        // floating-point type.
+        unsafe { &*(values as *const [Self] as *const [F]) }
+    }
+
+    #[inline]
+    fn is_valid_raw(value: F) -> bool {
+        value.is_nan() || C::check(value)
+    }
+
+    /// Returns the contained value, if any.
+    #[inline]
+    pub fn get(self) -> Option<NoisyFloat<F, C>> {
+        if self.value.is_nan() {
+            None
+        } else {
+            Some(NoisyFloat::new_unchecked(self.value))
+        }
+    }
+
+    /// Replaces the contained value.
+    #[inline]
+    pub fn set(&mut self, value: Option<NoisyFloat<F, C>>) {
+        *self = value.into();
+    }
+
+    /// Takes the contained value out, leaving `None` in its place.
+    #[inline]
+    pub fn take(&mut self) -> Option<NoisyFloat<F, C>> {
+        let value = self.get();
+        *self = Self::none();
+        value
+    }
+
+    /// Returns `true` if a value is contained.
+    #[inline]
+    pub fn is_some(self) -> bool {
+        !self.value.is_nan()
+    }
+
+    /// Returns `true` if no value is contained.
+    #[inline]
+    pub fn is_none(self) -> bool {
+        self.value.is_nan()
+    }
+
+    /// Returns the raw float representation, where `None` is represented by NaN.
+    #[inline]
+    pub fn raw(self) -> F {
+        self.value
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Clone for OptionalFloat<F, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Copy for OptionalFloat<F, C> {}
+
+impl<F: FloatCore, C: FloatChecker<F>> Default for OptionalFloat<F, C> {
+    #[inline]
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> PartialEq for OptionalFloat<F, C> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Eq for OptionalFloat<F, C> {}
+
+impl<F: FloatCore, C: FloatChecker<F>> Hash for OptionalFloat<F, C>
+where
+    NoisyFloat<F, C>: Hash,
+{
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.get().hash(state)
+    }
+}
+
+impl<F: FloatCore + fmt::Debug, C: FloatChecker<F>> fmt::Debug for OptionalFloat<F, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        fmt::Debug::fmt(&self.get(), f)
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> From<NoisyFloat<F, C>> for OptionalFloat<F, C> {
+    #[inline]
+    fn from(value: NoisyFloat<F, C>) -> Self {
+        Self::some(value)
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> From<Option<NoisyFloat<F, C>>> for OptionalFloat<F, C> {
+    #[inline]
+    fn from(value: Option<NoisyFloat<F, C>>) -> Self {
+        match value {
+            Some(value) => Self::some(value),
+            None => Self::none(),
+        }
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> From<OptionalFloat<F, C>> for Option<NoisyFloat<F, C>> {
+    #[inline]
+    fn from(value: OptionalFloat<F, C>) -> Self {
+        value.get()
+    }
+}
+
+#[cfg(feature = "serde-1")]
+impl<F: FloatCore + Serialize + 'static, C: FloatChecker<F>> Serialize for OptionalFloat<F, C> {
+    fn serialize<S: Serializer>(&self, ser: S) -> Result<S::Ok, S::Error> {
+        self.get().serialize(ser)
+    }
+}
+
+#[cfg(feature = "serde-1")]
+impl<'de, F: FloatCore + Deserialize<'de> + 'static, C: FloatChecker<F>> Deserialize<'de>
+    for OptionalFloat<F, C>
+{
+    fn deserialize<D: Deserializer<'de>>(de: D) -> Result<Self, D::Error> {
+        Option::<NoisyFloat<F, C>>::deserialize(de).map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use crate::prelude::*;
+    use core::mem::size_of;
+    use std::{format, vec, vec::Vec};
+
+    #[test]
+    fn optional_float() {
+        assert_eq!(size_of::<OptR64>(), size_of::<f64>());
+        assert_eq!(size_of::<OptN32>(), size_of::<f32>());
+
+        let mut value = OptR64::from(r64(2.5));
+        assert!(value.is_some());
+        assert_eq!(value.get(), Some(r64(2.5)));
+        assert_eq!(value.take(), Some(r64(2.5)));
+        assert!(value.is_none());
+        assert!(value.raw().is_nan());
+        value.set(Some(r64(-1.0)));
+        assert_eq!(Option::<R64>::from(value), Some(r64(-1.0)));
+
+        assert_eq!(OptN64::default(), OptN64::none());
+        assert_eq!(OptN64::from(None), OptN64::none());
+        assert_eq!(OptN64::from_raw(f64::INFINITY).get(), Some(N64::INFINITY));
+        assert_eq!(OptR64::try_from_raw(f64::INFINITY), None);
+        assert_eq!(format!("{:?}", OptR32::some(r32(1.5))), "Some(1.5)");
+        assert_eq!(format!("{:?}", OptR32::none()), "None");
+    }
+
+    #[test]
+    fn optional_float_slices() {
+        let negative_nan = -f64::NAN;
+        let mut raw = [1.0, f64::NAN, negative_nan, -0.5];
+        let values = OptR64::try_from_raw_slice(&raw).unwrap();
+        let present: Vec<R64> = values.iter().filter_map(|v| v.get()).collect();
+        assert_eq!(present, vec![r64(1.0), r64(-0.5)]);
+        assert_eq!(OptR64::as_raw_slice(values)[3], -0.5);
+
+        let values = OptR64::try_from_raw_slice_mut(&mut raw).unwrap();
+        values[1].set(Some(r64(7.0)));
+        values[0].set(None);
+        assert!(raw[0].is_nan());
+        assert_eq!(raw[1], 7.0);
+
+        assert!(OptR64::try_from_raw_slice(&[1.0, f64::INFINITY]).is_none());
+        assert_eq!(
+            OptN64::from_raw_slice(&[f64::INFINITY])[0].get(),
+            Some(N64::INFINITY)
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic]
+    fn optional_float_invalid_raw() {
+        let _ = OptR64::from_raw(f64::INFINITY);
+    }
+
+    #[cfg(feature = "serde-1")]
+    #[test]
+    fn serialize_optional_float() {
+        assert_eq!(
+            serde_json::to_string(&OptR64::some(r64(1.5))).unwrap(),
+            "1.5"
+        );
+        assert_eq!(serde_json::to_string(&OptR64::none()).unwrap(), "null");
+        let got: Vec<OptR64> = serde_json::from_str("[2.5, null]").unwrap();
+        assert_eq!(got, vec![OptR64::some(r64(2.5)), OptR64::none()]);
+    }
+}
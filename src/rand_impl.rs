@@ -0,0 +1,167 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Support for random number generation with the `rand` crate.
+
+use crate::{FloatChecker, NoisyFloat};
+use core::{fmt, marker::PhantomData};
+use rand::{
+    distributions::{
+        uniform::{SampleBorrow, SampleUniform, UniformFloat, UniformSampler},
+        Distribution, Open01, OpenClosed01, Standard,
+    },
+    Rng,
+};
+
+/// The sampler used by `rand` to sample `NoisyFloat` values uniformly from
+/// a range, e.g. with `Rng::gen_range` or `rand::distributions::Uniform`.
+///
+/// The values are sampled like the underlying float type and then checked,
+/// so a checker that accepts both ends of the range accepts all samples.
+/// Like for the float types, creating a sampler panics if the range is
+/// empty or its width is not finite.
+pub struct UniformNoisyFloat<F, C> {
+    sampler: UniformFloat<F>,
+    checker: PhantomData<C>,
+}
+
+impl<F: Clone, C> Clone for UniformNoisyFloat<F, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        UniformNoisyFloat {
+            sampler: self.sampler.clone(),
+            checker: PhantomData,
+        }
+    }
+}
+
+impl<F: Copy, C> Copy for UniformNoisyFloat<F, C> {}
+
+impl<F: fmt::Debug, C> fmt::Debug for UniformNoisyFloat<F, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("UniformNoisyFloat")
+            .field("sampler", &self.sampler)
+            .finish()
+    }
+}
+
+macro_rules! impl_rand {
+    ($f:ty) => {
+        impl<C: FloatChecker<$f>> Distribution<NoisyFloat<$f, C>> for Standard {
+            /// Samples a value uniformly from the half-open range `[0, 1)`.
+            #[inline]
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> NoisyFloat<$f, C> {
+                NoisyFloat::new(Distribution::<$f>::sample(self, rng))
+            }
+        }
+
+        impl<C: FloatChecker<$f>> Distribution<NoisyFloat<$f, C>> for Open01 {
+            /// Samples a value uniformly from the open range `(0, 1)`.
+            #[inline]
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> NoisyFloat<$f, C> {
+                NoisyFloat::new(Distribution::<$f>::sample(self, rng))
+            }
+        }
+
+        impl<C: FloatChecker<$f>> Distribution<NoisyFloat<$f, C>> for OpenClosed01 {
+            /// Samples a value uniformly from the half-open range `(0, 1]`.
+            #[inline]
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> NoisyFloat<$f, C> {
+                NoisyFloat::new(Distribution::<$f>::sample(self, rng))
+            }
+        }
+
+        impl<C: FloatChecker<$f>> SampleUniform for NoisyFloat<$f, C> {
+            type Sampler = UniformNoisyFloat<$f, C>;
+        }
+
+        impl<C: FloatChecker<$f>> UniformSampler for UniformNoisyFloat<$f, C> {
+            type X = NoisyFloat<$f, C>;
+
+            #[inline]
+            fn new<B1, B2>(low: B1, high: B2) -> Self
+            where
+                B1: SampleBorrow<Self::X> + Sized,
+                B2: SampleBorrow<Self::X> + Sized,
+            {
+                UniformNoisyFloat {
+                    sampler: UniformFloat::<$f>::new(low.borrow().raw(), high.borrow().raw()),
+                    checker: PhantomData,
+                }
+            }
+
+            #[inline]
+            fn new_inclusive<B1, B2>(low: B1, high: B2) -> Self
+            where
+                B1: SampleBorrow<Self::X> + Sized,
+                B2: SampleBorrow<Self::X> + Sized,
+            {
+                UniformNoisyFloat {
+                    sampler: UniformFloat::<$f>::new_inclusive(
+                        low.borrow().raw(),
+                        high.borrow().raw(),
+                    ),
+                    checker: PhantomData,
+                }
+            }
+
+            #[inline]
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Self::X {
+                NoisyFloat::new(self.sampler.sample(rng))
+            }
+        }
+    };
+}
+
+impl_rand!(f32);
+impl_rand!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use rand::{rngs::SmallRng, SeedableRng};
+
+    #[test]
+    fn random() {
+        use rand::distributions::Uniform;
+
+        let mut rng = SmallRng::seed_from_u64(5);
+        for _ in 0..1000 {
+            let value: R64 = rng.gen();
+            assert!((r64(0.0)..r64(1.0)).contains(&value));
+            let value: N32 = rng.sample(Open01);
+            assert!(value > 0.0 && value < 1.0);
+            let value: R32 = rng.sample(OpenClosed01);
+            assert!(value > 0.0 && value <= 1.0);
+
+            let value = rng.gen_range(r64(-2.0)..r64(3.0));
+            assert!((r64(-2.0)..r64(3.0)).contains(&value));
+            let value = rng.gen_range(r32(1.0)..=r32(1.5));
+            assert!((r32(1.0)..=r32(1.5)).contains(&value));
+            let value = rng.gen_range(n64(5.0)..=n64(5.0));
+            assert_eq!(value, 5.0);
+        }
+
+        let uniform = Uniform::new(r64(-1.0), r64(1.0));
+        let sum: R64 = rng.sample_iter(uniform).take(1000).sum();
+        assert!(sum.abs() < 100.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn random_empty_range() {
+        SmallRng::seed_from_u64(5).gen_range(r64(1.0)..r64(1.0));
+    }
+}
@@ -0,0 +1,546 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Iterators over evenly spaced `NoisyFloat` values.
+//!
+//! Each value is computed from its index rather than by repeatedly adding
+//! a step, so rounding errors do not accumulate, and the iterators can be
+//! traversed from both ends. The inputs are validated when the iterator is
+//! constructed, so that iterating never produces an invalid value.
+//!
+//! ```
+//! use noisy_float::{prelude::*, range::{arange, linspace}};
+//!
+//! let values: Vec<R64> = linspace(r64(0.0), r64(1.0), 5).unwrap().collect();
+//! assert_eq!(values, vec![r64(0.0), r64(0.25), r64(0.5), r64(0.75), r64(1.0)]);
+//!
+//! let values = arange(r64(0.0), r64(1.0), r64(0.1)).unwrap();
+//! assert_eq!(values.len(), 10);
+//! assert_eq!(values.rev().next(), Some(r64(0.9)));
+//! ```
+
+use crate::{iter::count_to_float, lerp, FloatChecker, NoisyFloat};
+use core::{fmt, iter::FusedIterator, marker::PhantomData};
+use num_traits::float::FloatCore;
+#[cfg(any(feature = "std", feature = "libm"))]
+use num_traits::Float;
+
+/// The error returned when the inputs of a range iterator are invalid.
+///
+/// With the `std` feature enabled, this implements `std::error::Error`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RangeError {
+    /// A bound or the step is infinite.
+    NonFinite,
+    /// The step is zero.
+    ZeroStep,
+    /// The number of values does not fit in a `usize`.
+    TooLong,
+    /// The bounds of a geometric range are zero or have different signs.
+    InvalidGeometricRange,
+    /// The base of a logarithmic range is not positive and finite.
+    InvalidBase,
+    /// A value of the range is rejected by the `FloatChecker`.
+    InvalidValue,
+}
+
+impl fmt::Display for RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            RangeError::NonFinite => write!(f, "infinite range bound or step"),
+            RangeError::ZeroStep => write!(f, "range step is zero"),
+            RangeError::TooLong => write!(f, "range has too many values"),
+            RangeError::InvalidGeometricRange => {
+                write!(f, "geometric range bounds are zero or have different signs")
+            }
+            RangeError::InvalidBase => write!(f, "logarithmic range base is not positive"),
+            RangeError::InvalidValue => write!(f, "range contains an illegal value"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for RangeError {}
+
+/// Interpolates between `start` at `index == 0` and `end` at `index == last`,
+/// exactly at both ends and without overflowing.
+/// With a single value, i.e. `last == 0`, this is `start`.
+#[inline]
+fn interpolate<F: FloatCore>(start: F, end: F, index: usize, last: usize) -> F {
+    if index == 0 {
+        return start;
+    }
+    lerp(
+        start,
+        end,
+        count_to_float::<F>(index) / count_to_float::<F>(last),
+    )
+}
+
+/// Returns `start + index * step`, without overflowing when only the
+/// intermediate product is out of range.
+#[inline]
+fn arange_value<F: FloatCore>(start: F, step: F, index: usize) -> F {
+    let index = count_to_float::<F>(index);
+    let value = start + step * index;
+    if value.is_finite() {
+        value
+    } else {
+        let two = F::one() + F::one();
+        (start / two + step * (index / two)) * two
+    }
+}
+
+/// Returns an iterator over `len` evenly spaced values from `start` to `end`,
+/// both included.
+///
+/// A single value is just `start`. Returns an error if a bound is infinite.
+#[inline]
+pub fn linspace<F: FloatCore, C: FloatChecker<F>>(
+    start: NoisyFloat<F, C>,
+    end: NoisyFloat<F, C>,
+    len: usize,
+) -> Result<Linspace<F, C>, RangeError> {
+    if !start.raw().is_finite() || !end.raw().is_finite() {
+        return Err(RangeError::NonFinite);
+    }
+    Ok(Linspace {
+        start: start.raw(),
+        end: end.raw(),
+        last: len.saturating_sub(1),
+        front: 0,
+        back: len,
+        checker: PhantomData,
+    })
+}
+
+/// Returns an iterator over the values `start + i * step` that are before `end`,
+/// like `start..end` for integers.
+///
+/// Returns an error if a bound or the step is infinite, if the step is zero,
+/// or if the number of values does not fit in a `usize`.
+pub fn arange<F: FloatCore, C: FloatChecker<F>>(
+    start: NoisyFloat<F, C>,
+    end: NoisyFloat<F, C>,
+    step: NoisyFloat<F, C>,
+) -> Result<Arange<F, C>, RangeError> {
+    let (start, end, step) = (start.raw(), end.raw(), step.raw());
+    if !start.is_finite() || !end.is_finite() || !step.is_finite() {
+        return Err(RangeError::NonFinite);
+    }
+    if step == F::zero() {
+        return Err(RangeError::ZeroStep);
+    }
+    let width = end - start;
+    let steps = if width.is_finite() {
+        width / step
+    } else {
+        // the bounds have different signs, so their halves can be subtracted
+        let two = F::one() + F::one();
+        (end / two - start / two) / step * two
+    }
+    .ceil();
+    let mut len = if steps > F::zero() {
+        steps.to_usize().ok_or(RangeError::TooLong)?
+    } else {
+        0
+    };
+    // rounding can put the last value on or past the end
+    let before_end = |value: F| {
+        if step > F::zero() {
+            value < end
+        } else {
+            value > end
+        }
+    };
+    while len > 0 && !before_end(arange_value(start, step, len - 1)) {
+        len -= 1;
+    }
+    Ok(Arange {
+        start,
+        step,
+        front: 0,
+        back: len,
+        checker: PhantomData,
+    })
+}
+
+/// Returns an iterator over `len` values from `base^start` to `base^end`,
+/// whose exponents are evenly spaced.
+///
+/// Returns an error if an exponent is infinite, if the base is not positive
+/// and finite, or if a value is rejected by the `FloatChecker`.
+///
+/// Requires either the `std` or the `libm` feature.
+///
+/// ```
+/// use noisy_float::{prelude::*, range::logspace};
+///
+/// let values: Vec<R64> = logspace(r64(0.0), r64(3.0), 4, r64(10.0)).unwrap().collect();
+/// assert_eq!(values, vec![r64(1.0), r64(10.0), r64(100.0), r64(1000.0)]);
+/// ```
+#[cfg(any(feature = "std", feature = "libm"))]
+pub fn logspace<F: Float + FloatCore, C: FloatChecker<F>>(
+    start: NoisyFloat<F, C>,
+    end: NoisyFloat<F, C>,
+    len: usize,
+    base: NoisyFloat<F, C>,
+) -> Result<Logspace<F, C>, RangeError> {
+    if !Float::is_finite(start.raw()) || !Float::is_finite(end.raw()) {
+        return Err(RangeError::NonFinite);
+    }
+    let base = base.raw();
+    if !(base > F::zero() && Float::is_finite(base)) {
+        return Err(RangeError::InvalidBase);
+    }
+    Logspace {
+        start: start.raw(),
+        end: end.raw(),
+        base,
+        scale: F::one(),
+        endpoints: None,
+        last: len.saturating_sub(1),
+        front: 0,
+        back: len,
+        checker: PhantomData,
+    }
+    .validated()
+}
+
+/// Returns an iterator over `len` values from `start` to `end`, both included,
+/// where each value is a constant multiple of the previous one.
+///
+/// Returns an error if a bound is infinite, if the bounds are zero or have
+/// different signs, or if a value is rejected by the `FloatChecker`.
+///
+/// Requires either the `std` or the `libm` feature.
+///
+/// ```
+/// use noisy_float::{prelude::*, range::geomspace};
+///
+/// let values: Vec<R64> = geomspace(r64(-1.0), r64(-1000.0), 4).unwrap().collect();
+/// assert_eq!(values[0], r64(-1.0));
+/// assert!((values[2] + 100.0).abs() < 1e-12);
+/// assert_eq!(values[3], r64(-1000.0));
+/// ```
+#[cfg(any(feature = "std", feature = "libm"))]
+pub fn geomspace<F: Float + FloatCore, C: FloatChecker<F>>(
+    start: NoisyFloat<F, C>,
+    end: NoisyFloat<F, C>,
+    len: usize,
+) -> Result<Logspace<F, C>, RangeError> {
+    let (start, end) = (start.raw(), end.raw());
+    if !Float::is_finite(start) || !Float::is_finite(end) {
+        return Err(RangeError::NonFinite);
+    }
+    let zero = F::zero();
+    if !((start > zero && end > zero) || (start < zero && end < zero)) {
+        return Err(RangeError::InvalidGeometricRange);
+    }
+    Logspace {
+        start: Float::ln(Float::abs(start)),
+        end: Float::ln(Float::abs(end)),
+        base: Float::exp(F::one()),
+        scale: Float::signum(start),
+        endpoints: Some((start, end)),
+        last: len.saturating_sub(1),
+        front: 0,
+        back: len,
+        checker: PhantomData,
+    }
+    .validated()
+}
+
+/// An iterator over evenly spaced values, created by `linspace`.
+pub struct Linspace<F: FloatCore, C: FloatChecker<F>> {
+    start: F,
+    end: F,
+    last: usize,
+    front: usize,
+    back: usize,
+    checker: PhantomData<C>,
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Linspace<F, C> {
+    #[inline]
+    fn get(&self, index: usize) -> NoisyFloat<F, C> {
+        NoisyFloat::new(interpolate(self.start, self.end, index, self.last))
+    }
+}
+
+/// An iterator over values separated by a step, created by `arange`.
+pub struct Arange<F: FloatCore, C: FloatChecker<F>> {
+    start: F,
+    step: F,
+    front: usize,
+    back: usize,
+    checker: PhantomData<C>,
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Arange<F, C> {
+    #[inline]
+    fn get(&self, index: usize) -> NoisyFloat<F, C> {
+        NoisyFloat::new(arange_value(self.start, self.step, index))
+    }
+}
+
+/// An iterator over values with evenly spaced logarithms,
+/// created by `logspace` or `geomspace`.
+///
+/// Requires either the `std` or the `libm` feature.
+#[cfg(any(feature = "std", feature = "libm"))]
+pub struct Logspace<F: Float + FloatCore, C: FloatChecker<F>> {
+    start: F,
+    end: F,
+    base: F,
+    scale: F,
+    endpoints: Option<(F, F)>,
+    last: usize,
+    front: usize,
+    back: usize,
+    checker: PhantomData<C>,
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<F: Float + FloatCore, C: FloatChecker<F>> Logspace<F, C> {
+    #[inline]
+    fn raw(&self, index: usize) -> F {
+        match self.endpoints {
+            Some((start, _)) if index == 0 => start,
+            Some((_, end)) if index == self.last => end,
+            _ => {
+                let exponent = interpolate(self.start, self.end, index, self.last);
+                self.scale * Float::powf(self.base, exponent)
+            }
+        }
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> NoisyFloat<F, C> {
+        NoisyFloat::new(self.raw(index))
+    }
+
+    /// Checks the first and last values, which bound the others.
+    fn validated(self) -> Result<Self, RangeError> {
+        if self.back > 0 && !(C::check(self.raw(0)) && C::check(self.raw(self.last))) {
+            return Err(RangeError::InvalidValue);
+        }
+        Ok(self)
+    }
+}
+
+macro_rules! impl_index_iterator {
+    ($name:ident, [$($field:ident),+], $($bound:tt)+) => {
+        impl<F: $($bound)+, C: FloatChecker<F>> Iterator for $name<F, C> {
+            type Item = NoisyFloat<F, C>;
+
+            #[inline]
+            fn next(&mut self) -> Option<Self::Item> {
+                if self.front == self.back {
+                    return None;
+                }
+                self.front += 1;
+                Some(self.get(self.front - 1))
+            }
+
+            #[inline]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                let len = self.back - self.front;
+                (len, Some(len))
+            }
+
+            #[inline]
+            fn nth(&mut self, n: usize) -> Option<Self::Item> {
+                self.front += n.min(self.back - self.front);
+                self.next()
+            }
+        }
+
+        impl<F: $($bound)+, C: FloatChecker<F>> DoubleEndedIterator for $name<F, C> {
+            #[inline]
+            fn next_back(&mut self) -> Option<Self::Item> {
+                if self.front == self.back {
+                    return None;
+                }
+                self.back -= 1;
+                Some(self.get(self.back))
+            }
+
+            #[inline]
+            fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+                self.back -= n.min(self.back - self.front);
+                self.next_back()
+            }
+        }
+
+        impl<F: $($bound)+, C: FloatChecker<F>> ExactSizeIterator for $name<F, C> {}
+
+        impl<F: $($bound)+, C: FloatChecker<F>> FusedIterator for $name<F, C> {}
+
+        impl<F: $($bound)+, C: FloatChecker<F>> Clone for $name<F, C> {
+            #[inline]
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<F: $($bound)+, C: FloatChecker<F>> Copy for $name<F, C> {}
+
+        impl<F: $($bound)+ + fmt::Debug, C: FloatChecker<F>> fmt::Debug for $name<F, C> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+                f.debug_struct(stringify!($name))
+                    $(.field(stringify!($field), &self.$field))+
+                    .finish()
+            }
+        }
+    };
+}
+
+impl_index_iterator!(Linspace, [start, end, last, front, back], FloatCore);
+impl_index_iterator!(Arange, [start, step, front, back], FloatCore);
+#[cfg(any(feature = "std", feature = "libm"))]
+impl_index_iterator!(
+    Logspace,
+    [start, end, base, scale, endpoints, last, front, back],
+    Float + FloatCore
+);
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+    use super::*;
+    use crate::prelude::*;
+    use std::format;
+
+    #[test]
+    fn linspace_values() {
+        let values = linspace(r64(-1.0), r64(1.0), 5).unwrap();
+        assert_eq!(values.len(), 5);
+        assert!(values.eq([-1.0, -0.5, 0.0, 0.5, 1.0].iter().map(|&x| r64(x))));
+        assert!(values
+            .rev()
+            .eq([1.0, 0.5, 0.0, -0.5, -1.0].iter().map(|&x| r64(x))));
+        assert_eq!(values.clone().nth(4), Some(r64(1.0)));
+        assert_eq!(values.clone().nth(5), None);
+        assert_eq!(values.clone().nth_back(1), Some(r64(0.5)));
+
+        assert_eq!(
+            linspace(r32(2.0), r32(3.0), 1).unwrap().next(),
+            Some(r32(2.0))
+        );
+        assert_eq!(linspace(r32(2.0), r32(3.0), 0).unwrap().next(), None);
+        let extreme = linspace(r32(-f32::MAX), r32(f32::MAX), 3).unwrap();
+        assert!(extreme.eq([r32(-f32::MAX), r32(0.0), r32(f32::MAX)].iter().copied()));
+        assert_eq!(
+            linspace(n64(0.0), N64::INFINITY, 3).unwrap_err(),
+            RangeError::NonFinite
+        );
+    }
+
+    #[test]
+    fn linspace_without_accumulation() {
+        let values = linspace(r64(0.0), r64(1.0), 11).unwrap();
+        assert_eq!(values.clone().nth(3), Some(r64(0.3)));
+        assert_eq!(values.clone().next_back(), Some(r64(1.0)));
+    }
+
+    #[test]
+    fn arange_values() {
+        let values = arange(r64(1.0), r64(2.0), r64(0.25)).unwrap();
+        assert!(values.eq([1.0, 1.25, 1.5, 1.75].iter().map(|&x| r64(x))));
+        let values = arange(r64(1.0), r64(-1.0), r64(-0.5)).unwrap();
+        assert!(values.eq([1.0, 0.5, 0.0, -0.5].iter().map(|&x| r64(x))));
+        assert_eq!(arange(r64(1.0), r64(-1.0), r64(0.5)).unwrap().len(), 0);
+        let values = arange(r64(1.0), r64(1.3), r64(0.1)).unwrap();
+        assert_eq!(values.len(), 3);
+        assert!(values.clone().next_back().unwrap() < 1.3);
+
+        assert_eq!(
+            arange(r64(0.0), r64(1.0), r64(0.0)).unwrap_err(),
+            RangeError::ZeroStep
+        );
+        assert_eq!(
+            arange(r64(0.0), r64(1.0e300), r64(1.0e-300)).unwrap_err(),
+            RangeError::TooLong
+        );
+        assert_eq!(
+            arange(n64(0.0), n64(1.0), N64::INFINITY).unwrap_err(),
+            RangeError::NonFinite
+        );
+    }
+
+    #[test]
+    fn arange_full_range() {
+        // 2^1023, so that the values are exact although `3 * step` overflows
+        let big = f64::from_bits(0x7fe0_0000_0000_0000);
+        let values = arange(r64(-big), r64(f64::MAX), r64(0.75 * big)).unwrap();
+        let expected = [-big, -0.25 * big, 0.5 * big, 1.25 * big];
+        assert!(values.eq(expected.iter().map(|&x| r64(x))));
+        let values = arange(r32(f32::MAX), r32(-f32::MAX), r32(-f32::MAX)).unwrap();
+        assert!(values.eq([r32(f32::MAX), r32(0.0)].iter().copied()));
+        assert_eq!(
+            arange(r64(-f64::MAX), r64(f64::MAX), r64(1.0)).unwrap_err(),
+            RangeError::TooLong
+        );
+    }
+
+    #[test]
+    fn debug_fields() {
+        let values = linspace(r64(0.0), r64(1.0), usize::MAX).unwrap();
+        assert_eq!(
+            format!("{:?}", values),
+            format!(
+                "Linspace {{ start: 0.0, end: 1.0, last: {}, front: 0, back: {} }}",
+                usize::MAX - 1,
+                usize::MAX
+            )
+        );
+        let mut values = arange(r32(0.0), r32(1.0), r32(0.25)).unwrap();
+        values.next();
+        assert_eq!(
+            format!("{:?}", values),
+            "Arange { start: 0.0, step: 0.25, front: 1, back: 4 }"
+        );
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn logarithmic_values() {
+        let values = logspace(r64(0.0), r64(2.0), 3, r64(2.0)).unwrap();
+        assert!(values.eq([1.0, 2.0, 4.0].iter().map(|&x| r64(x))));
+        assert_eq!(
+            logspace(r64(0.0), r64(400.0), 3, r64(10.0)).unwrap_err(),
+            RangeError::InvalidValue
+        );
+        assert_eq!(
+            logspace(r64(0.0), r64(1.0), 3, r64(-2.0)).unwrap_err(),
+            RangeError::InvalidBase
+        );
+
+        let values = geomspace(r64(1.0), r64(1000.0), 4).unwrap();
+        assert_eq!(values.len(), 4);
+        assert_eq!(values.clone().next_back(), Some(r64(1000.0)));
+        for (value, expected) in values.zip([1.0, 10.0, 100.0, 1000.0].iter()) {
+            assert!((value - *expected).abs() < 1e-9);
+        }
+        assert_eq!(
+            geomspace(r64(-1.0), r64(1.0), 3).unwrap_err(),
+            RangeError::InvalidGeometricRange
+        );
+        assert_eq!(
+            geomspace(r64(0.0), r64(1.0), 3).unwrap_err(),
+            RangeError::InvalidGeometricRange
+        );
+    }
+}
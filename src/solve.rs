@@ -0,0 +1,712 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Root finding for functions of `NoisyFloat` values.
+//!
+//! The solvers search for a root within a bracket, i.e. an interval at whose
+//! ends the function has different signs, and keep the root bracketed while
+//! they iterate. Every point at which the function is evaluated is a valid
+//! `NoisyFloat` within the bracket, and the function itself returns
+//! `NoisyFloat` values, so a NaN cannot silently corrupt the search.
+//! The function can also return `Option`, where `None` stops the search with
+//! `SolveError::EvaluationFailed`.
+//!
+//! ```
+//! use noisy_float::{prelude::*, solve::{brent, SolveOptions, Termination}};
+//!
+//! let solution = brent(|x: R64| x * x - 2.0, r64(0.0), r64(2.0), SolveOptions::default()).unwrap();
+//! assert!((solution.root - core::f64::consts::SQRT_2).abs() < 1e-15);
+//! assert_eq!(solution.termination, Termination::Converged);
+//! ```
+
+use crate::{FloatChecker, NoisyFloat};
+use core::fmt;
+use num_traits::float::FloatCore;
+
+/// Options controlling when a solver stops.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SolveOptions<F> {
+    /// The solver stops when the root is known to within this absolute
+    /// tolerance. With the default of zero, the solver stops when the
+    /// bracket cannot be narrowed further at the precision of the float type.
+    pub tolerance: F,
+    /// The maximum number of iterations, each evaluating the function once.
+    /// The default is 200.
+    pub max_iterations: usize,
+}
+
+impl<F: FloatCore> Default for SolveOptions<F> {
+    #[inline]
+    fn default() -> Self {
+        SolveOptions {
+            tolerance: F::zero(),
+            max_iterations: 200,
+        }
+    }
+}
+
+/// The reason a solver stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Termination {
+    /// The function is zero at the root.
+    ExactRoot,
+    /// The bracket around the root is within the tolerance.
+    Converged,
+    /// The maximum number of iterations was reached before converging.
+    MaxIterations,
+}
+
+/// The result of a solver.
+pub struct Solution<F: FloatCore, C: FloatChecker<F>> {
+    /// The best estimate of the root, at which the function was evaluated.
+    pub root: NoisyFloat<F, C>,
+    /// The value of the function at `root`.
+    pub value: NoisyFloat<F, C>,
+    /// The lower and upper end of the final bracket around the root.
+    pub bracket: (NoisyFloat<F, C>, NoisyFloat<F, C>),
+    /// The number of iterations, not counting the evaluations at the ends
+    /// of the initial bracket.
+    pub iterations: usize,
+    /// The reason the solver stopped.
+    pub termination: Termination,
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Clone for Solution<F, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Copy for Solution<F, C> {}
+
+impl<F: FloatCore + fmt::Debug, C: FloatChecker<F>> fmt::Debug for Solution<F, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("Solution")
+            .field("root", &self.root)
+            .field("value", &self.value)
+            .field("bracket", &self.bracket)
+            .field("iterations", &self.iterations)
+            .field("termination", &self.termination)
+            .finish()
+    }
+}
+
+/// The error returned when a solver cannot search for a root.
+///
+/// With the `std` feature enabled, this implements `std::error::Error`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolveError<F> {
+    /// An end of the bracket is infinite.
+    NonFiniteBracket,
+    /// The function has the same sign at both ends of the bracket.
+    NotBracketed,
+    /// The function returned `None` at the given point.
+    EvaluationFailed {
+        /// The point at which the function was evaluated.
+        at: F,
+    },
+}
+
+impl<F: fmt::Display> fmt::Display for SolveError<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match self {
+            SolveError::NonFiniteBracket => write!(f, "infinite bracket"),
+            SolveError::NotBracketed => {
+                write!(f, "function has the same sign at both ends of the bracket")
+            }
+            SolveError::EvaluationFailed { at } => {
+                write!(f, "function evaluation failed at {}", at)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F: fmt::Debug + fmt::Display> std::error::Error for SolveError<F> {}
+
+type SolveResult<F, C> = Result<Solution<F, C>, SolveError<F>>;
+
+#[inline]
+fn evaluate<F, C, T, R>(
+    f: &mut impl FnMut(NoisyFloat<F, C>) -> R,
+    x: NoisyFloat<F, C>,
+) -> Result<T, SolveError<F>>
+where
+    F: FloatCore,
+    C: FloatChecker<F>,
+    R: Into<Option<T>>,
+{
+    f(x).into()
+        .ok_or(SolveError::EvaluationFailed { at: x.raw() })
+}
+
+/// Evaluates the function at the ends of the bracket, returning the ends and
+/// values ordered by position, or a solution if an end is an exact root.
+#[allow(clippy::type_complexity)]
+fn initial_bracket<F, C, T>(
+    lower: NoisyFloat<F, C>,
+    upper: NoisyFloat<F, C>,
+    mut f: impl FnMut(NoisyFloat<F, C>) -> Result<(NoisyFloat<F, C>, T), SolveError<F>>,
+) -> Result<Result<[(NoisyFloat<F, C>, NoisyFloat<F, C>, T); 2], Solution<F, C>>, SolveError<F>>
+where
+    F: FloatCore,
+    C: FloatChecker<F>,
+{
+    if !lower.is_finite() || !upper.is_finite() {
+        return Err(SolveError::NonFiniteBracket);
+    }
+    let (lower, upper) = (lower.min(upper), lower.max(upper));
+    let mut ends = [lower, upper].map(|x| f(x).map(|(value, extra)| (x, value, extra)));
+    for end in &mut ends {
+        if let Ok((x, value, _)) = *end {
+            if value.raw() == F::zero() {
+                return Ok(Err(Solution {
+                    root: x,
+                    value,
+                    bracket: (x, x),
+                    iterations: 0,
+                    termination: Termination::ExactRoot,
+                }));
+            }
+        }
+    }
+    let [low, high] = ends;
+    let (low, high) = (low?, high?);
+    if (low.1.raw() < F::zero()) == (high.1.raw() < F::zero()) {
+        return Err(SolveError::NotBracketed);
+    }
+    Ok(Ok([low, high]))
+}
+
+#[inline]
+fn midpoint<F: FloatCore, C: FloatChecker<F>>(
+    a: NoisyFloat<F, C>,
+    b: NoisyFloat<F, C>,
+) -> NoisyFloat<F, C> {
+    let half = F::one() / (F::one() + F::one());
+    NoisyFloat::new(a.raw() * half + b.raw() * half)
+}
+
+/// Returns the one of two evaluated points at which the function is smaller.
+#[inline]
+fn closest<F: FloatCore, C: FloatChecker<F>>(
+    a: (NoisyFloat<F, C>, NoisyFloat<F, C>),
+    b: (NoisyFloat<F, C>, NoisyFloat<F, C>),
+) -> (NoisyFloat<F, C>, NoisyFloat<F, C>) {
+    if b.1.abs() < a.1.abs() {
+        b
+    } else {
+        a
+    }
+}
+
+/// Finds a root of `f` between `lower` and `upper` by bisection.
+///
+/// Bisection halves the bracket in every iteration, which is slower than
+/// `brent`, but it only relies on the sign of the function.
+///
+/// Returns an error if an end of the bracket is infinite, if the function
+/// has the same sign at both ends, or if the function returns `None`.
+pub fn bisection<F, C, R>(
+    mut f: impl FnMut(NoisyFloat<F, C>) -> R,
+    lower: NoisyFloat<F, C>,
+    upper: NoisyFloat<F, C>,
+    options: SolveOptions<F>,
+) -> SolveResult<F, C>
+where
+    F: FloatCore,
+    C: FloatChecker<F>,
+    R: Into<Option<NoisyFloat<F, C>>>,
+{
+    let ends = initial_bracket(lower, upper, |x| Ok((evaluate(&mut f, x)?, ())))?;
+    let [(mut a, mut fa, _), (mut b, mut fb, _)] = match ends {
+        Ok(ends) => ends,
+        Err(solution) => return Ok(solution),
+    };
+    let solution = |a, fa, b, fb, iterations, termination| {
+        let (root, value) = closest((a, fa), (b, fb));
+        Solution {
+            root,
+            value,
+            bracket: (a, b),
+            iterations,
+            termination,
+        }
+    };
+    for iteration in 1..=options.max_iterations {
+        let m = midpoint(a, b);
+        if m <= a || m >= b || b.raw() - a.raw() <= options.tolerance {
+            return Ok(solution(
+                a,
+                fa,
+                b,
+                fb,
+                iteration - 1,
+                Termination::Converged,
+            ));
+        }
+        let fm = evaluate(&mut f, m)?;
+        if fm.raw() == F::zero() {
+            return Ok(Solution {
+                root: m,
+                value: fm,
+                bracket: (m, m),
+                iterations: iteration,
+                termination: Termination::ExactRoot,
+            });
+        }
+        if (fm.raw() < F::zero()) == (fa.raw() < F::zero()) {
+            a = m;
+            fa = fm;
+        } else {
+            b = m;
+            fb = fm;
+        }
+    }
+    let termination = if b.raw() - a.raw() <= options.tolerance {
+        Termination::Converged
+    } else {
+        Termination::MaxIterations
+    };
+    Ok(solution(a, fa, b, fb, options.max_iterations, termination))
+}
+
+/// Finds a root of `f` between `lower` and `upper` with Brent's method.
+///
+/// Brent's method combines inverse quadratic interpolation and the secant
+/// method, which converge quickly for smooth functions, with bisection,
+/// which guarantees that the bracket shrinks.
+///
+/// Returns an error if an end of the bracket is infinite, if the function
+/// has the same sign at both ends, or if the function returns `None`.
+pub fn brent<F, C, R>(
+    mut f: impl FnMut(NoisyFloat<F, C>) -> R,
+    lower: NoisyFloat<F, C>,
+    upper: NoisyFloat<F, C>,
+    options: SolveOptions<F>,
+) -> SolveResult<F, C>
+where
+    F: FloatCore,
+    C: FloatChecker<F>,
+    R: Into<Option<NoisyFloat<F, C>>>,
+{
+    let ends = initial_bracket(lower, upper, |x| Ok((evaluate(&mut f, x)?, ())))?;
+    let [(a, fa, _), (b, fb, _)] = match ends {
+        Ok(ends) => ends,
+        Err(solution) => return Ok(solution),
+    };
+    let zero = F::zero();
+    let one = F::one();
+    let two = one + one;
+    let three = two + one;
+    let half = one / two;
+
+    // `b` is the best estimate, `a` the previous one,
+    // and the root is bracketed between `b` and `c`
+    let (mut a, mut fa, mut b, mut fb) = (a.raw(), fa.raw(), b.raw(), fb.raw());
+    let (mut c, mut fc) = (b, fb);
+    let mut step = b - a;
+    let mut previous_step = step;
+    let solution = |b: F, fb: F, c: F, iterations, termination| {
+        let (root, other) = (NoisyFloat::new(b), NoisyFloat::new(c));
+        Solution {
+            root,
+            value: NoisyFloat::new(fb),
+            bracket: (root.min(other), root.max(other)),
+            iterations,
+            termination,
+        }
+    };
+    for iteration in 0..=options.max_iterations {
+        if (fb > zero) == (fc > zero) {
+            c = a;
+            fc = fa;
+            step = b - a;
+            previous_step = step;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+        if fb == zero {
+            return Ok(solution(b, fb, b, iteration, Termination::ExactRoot));
+        }
+        let tolerance = two * F::epsilon() * b.abs() + half * options.tolerance;
+        // halve before subtracting, so that this does not overflow
+        let to_middle = c * half - b * half;
+        if to_middle.abs() <= tolerance || to_middle == zero {
+            return Ok(solution(b, fb, c, iteration, Termination::Converged));
+        }
+        if iteration == options.max_iterations {
+            break;
+        }
+
+        if previous_step.abs() >= tolerance && fa.abs() > fb.abs() {
+            // try interpolation
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (two * to_middle * s, one - s)
+            } else {
+                let q = fa / fc;
+                let r = fb / fc;
+                (
+                    s * (two * to_middle * q * (q - r) - (b - a) * (r - one)),
+                    (q - one) * (r - one) * (s - one),
+                )
+            };
+            if p > zero {
+                q = -q;
+            }
+            p = p.abs();
+            let limit =
+                (three * to_middle * q - (tolerance * q).abs()).min((previous_step * q).abs());
+            if two * p < limit {
+                previous_step = step;
+                step = p / q;
+            } else {
+                step = to_middle;
+                previous_step = step;
+            }
+        } else {
+            step = to_middle;
+            previous_step = step;
+        }
+        let next = if step.abs() > tolerance {
+            b + step
+        } else if to_middle > zero {
+            b + tolerance
+        } else {
+            b - tolerance
+        };
+        a = b;
+        fa = fb;
+        // keep the trial point within the bracket, also when rounding or
+        // overflow in the interpolation would move it out
+        b = if b.min(c) <= next && next <= b.max(c) {
+            next
+        } else {
+            b + to_middle
+        };
+        fb = evaluate(&mut f, NoisyFloat::new(b))?.raw();
+    }
+    Ok(solution(
+        b,
+        fb,
+        c,
+        options.max_iterations,
+        Termination::MaxIterations,
+    ))
+}
+
+/// Finds a root of a function between `lower` and `upper` with Newton's
+/// method, starting from `guess`, safeguarded by bisection.
+///
+/// The function returns its value and its derivative at a point.
+/// A Newton step is only taken when it stays within the bracket and shrinks
+/// it quickly enough, otherwise the bracket is bisected, so that this
+/// converges even when the derivative is zero or misleading.
+///
+/// Returns an error if an end of the bracket is infinite, if the function
+/// has the same sign at both ends, or if the function returns `None`.
+///
+/// ```
+/// use noisy_float::{prelude::*, solve::{newton, SolveOptions}};
+///
+/// let cube = |x: R64| (x * x * x - 10.0, x * x * 3.0);
+/// let solution = newton(cube, r64(1.0), r64(0.0), r64(5.0), SolveOptions::default()).unwrap();
+/// assert!((solution.root - 2.154_434_690_031_884).abs() < 1e-14);
+/// ```
+pub fn newton<F, C, R>(
+    mut f: impl FnMut(NoisyFloat<F, C>) -> R,
+    guess: NoisyFloat<F, C>,
+    lower: NoisyFloat<F, C>,
+    upper: NoisyFloat<F, C>,
+    options: SolveOptions<F>,
+) -> SolveResult<F, C>
+where
+    F: FloatCore,
+    C: FloatChecker<F>,
+    R: Into<Option<(NoisyFloat<F, C>, NoisyFloat<F, C>)>>,
+{
+    let ends = initial_bracket(lower, upper, |x| evaluate(&mut f, x))?;
+    let [(a, fa, _), (b, _, _)] = match ends {
+        Ok(ends) => ends,
+        Err(solution) => return Ok(solution),
+    };
+    // the function is negative at `low` and positive at `high`
+    let (mut low, mut high) = if fa.raw() < F::zero() { (a, b) } else { (b, a) };
+    let mut x = guess.clamp(a, b);
+    let (mut value, mut derivative) = evaluate(&mut f, x)?;
+    let mut previous_step = b.raw() - a.raw();
+    let mut step = previous_step;
+    let two = F::one() + F::one();
+    let solution =
+        |x, value, low: NoisyFloat<F, C>, high: NoisyFloat<F, C>, iterations, termination| {
+            Solution {
+                root: x,
+                value,
+                bracket: (low.min(high), low.max(high)),
+                iterations,
+                termination,
+            }
+        };
+    for iteration in 1..=options.max_iterations {
+        if value.raw() == F::zero() {
+            return Ok(solution(
+                x,
+                value,
+                x,
+                x,
+                iteration - 1,
+                Termination::ExactRoot,
+            ));
+        }
+        if value.raw() < F::zero() {
+            low = x;
+        } else {
+            high = x;
+        }
+        let (fx, dfx) = (value.raw(), derivative.raw());
+        let outside =
+            ((x.raw() - high.raw()) * dfx - fx) * ((x.raw() - low.raw()) * dfx - fx) > F::zero();
+        let slow = (two * fx).abs() > (previous_step * dfx).abs();
+        let newton = if outside || slow || dfx == F::zero() {
+            None
+        } else {
+            // the sign test above can fail to catch steps out of the bracket
+            // when the distances to its ends overflow
+            NoisyFloat::try_new(x.raw() - fx / dfx)
+                .filter(|&next| low.min(high) <= next && next <= low.max(high))
+        };
+        previous_step = step;
+        let next = match newton {
+            Some(next) => {
+                step = fx / dfx;
+                next
+            }
+            None => {
+                let middle = midpoint(low, high);
+                step = x.raw() - middle.raw();
+                middle
+            }
+        };
+        let width = (high.raw() - low.raw()).abs();
+        if next == x || next == low || next == high || width <= options.tolerance {
+            return Ok(solution(
+                x,
+                value,
+                low,
+                high,
+                iteration - 1,
+                Termination::Converged,
+            ));
+        }
+        x = next;
+        let evaluated = evaluate(&mut f, x)?;
+        value = evaluated.0;
+        derivative = evaluated.1;
+    }
+    let termination = if value.raw() == F::zero() {
+        Termination::ExactRoot
+    } else {
+        Termination::MaxIterations
+    };
+    Ok(solution(
+        x,
+        value,
+        low,
+        high,
+        options.max_iterations,
+        termination,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    type Solver = fn(
+        &mut dyn FnMut(R64) -> Option<R64>,
+        R64,
+        R64,
+    ) -> SolveResult<f64, crate::checkers::FiniteChecker>;
+
+    fn solvers() -> [(&'static str, Solver); 3] {
+        [
+            ("bisection", |f, a, b| {
+                bisection(f, a, b, SolveOptions::default())
+            }),
+            ("brent", |f, a, b| brent(f, a, b, SolveOptions::default())),
+            ("newton", |f, a, b| {
+                // a numerical derivative is good enough for these tests
+                let mut g = |x: R64| {
+                    let h = r64(1e-7);
+                    let derivative = (f(x + h)? - f(x - h)?) / (h * 2.0);
+                    Some((f(x)?, derivative))
+                };
+                newton(&mut g, midpoint(a, b), a, b, SolveOptions::default())
+            }),
+        ]
+    }
+
+    #[test]
+    fn finds_roots() {
+        for (name, solve) in solvers().iter() {
+            let solution = solve(&mut |x| Some(x * x - 2.0), r64(0.0), r64(2.0)).unwrap();
+            assert!(
+                (solution.root - core::f64::consts::SQRT_2).abs() < 1e-14,
+                "{}",
+                name
+            );
+            assert!(solution.bracket.0 <= solution.root && solution.root <= solution.bracket.1);
+            assert_ne!(solution.termination, Termination::MaxIterations, "{}", name);
+
+            let solution =
+                solve(&mut |x| Some(x * x * x - x * 2.0 - 5.0), r64(3.0), r64(2.0)).unwrap();
+            assert!(
+                (solution.root - 2.094_551_481_542_326_6).abs() < 1e-14,
+                "{}",
+                name
+            );
+
+            let solution = solve(&mut |x| Some(x - 0.5), r64(0.0), r64(1.0)).unwrap();
+            assert_eq!(solution.root, r64(0.5), "{}", name);
+            assert_eq!(solution.termination, Termination::ExactRoot, "{}", name);
+
+            let solution = solve(&mut |x| Some(x), r64(0.0), r64(1.0)).unwrap();
+            assert_eq!(solution.iterations, 0, "{}", name);
+        }
+    }
+
+    #[test]
+    fn iteration_counts() {
+        let mut bisection_evaluations = 0;
+        let f = |x: R64| x * x * x - x * 2.0 - 5.0;
+        let solution = bisection(
+            |x| {
+                bisection_evaluations += 1;
+                f(x)
+            },
+            r64(2.0),
+            r64(3.0),
+            SolveOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(solution.iterations + 2, bisection_evaluations);
+        assert!(solution.iterations > 40);
+
+        let solution = brent(f, r64(2.0), r64(3.0), SolveOptions::default()).unwrap();
+        assert!(solution.iterations < 15);
+
+        let options = SolveOptions {
+            tolerance: 1e-3,
+            max_iterations: 100,
+        };
+        let solution = bisection(f, r64(2.0), r64(3.0), options).unwrap();
+        assert_eq!(solution.termination, Termination::Converged);
+        assert!(solution.bracket.1 - solution.bracket.0 <= 1e-3);
+
+        let options = SolveOptions {
+            tolerance: 0.0,
+            max_iterations: 3,
+        };
+        let solution = bisection(f, r64(2.0), r64(3.0), options).unwrap();
+        assert_eq!(solution.termination, Termination::MaxIterations);
+        assert_eq!(solution.iterations, 3);
+        assert_eq!(solution.bracket, (r64(2.0), r64(2.125)));
+    }
+
+    #[test]
+    fn errors() {
+        for (name, solve) in solvers().iter() {
+            let error = solve(&mut |x| Some(x * x + 1.0), r64(-1.0), r64(1.0)).unwrap_err();
+            assert_eq!(error, SolveError::NotBracketed, "{}", name);
+
+            let mut f = |x: R64| if x < 0.25 { Some(x - 0.3) } else { None };
+            let error = solve(&mut f, r64(0.0), r64(1.0)).unwrap_err();
+            assert!(
+                matches!(error, SolveError::EvaluationFailed { .. }),
+                "{}",
+                name
+            );
+        }
+        let error = brent(|x: N64| x, n64(0.0), N64::INFINITY, SolveOptions::default());
+        assert_eq!(error.unwrap_err(), SolveError::NonFiniteBracket);
+    }
+
+    #[test]
+    fn newton_safeguard() {
+        // Newton's method alone would cycle between -1 and 1 here
+        let f = |x: R64| (x * x * x - x * 2.0 + 2.0, x * x * 3.0 - 2.0);
+        let solution = newton(f, r64(0.0), r64(-3.0), r64(3.0), SolveOptions::default()).unwrap();
+        assert!((solution.root + 1.769_292_354_238_631_4).abs() < 1e-14);
+
+        // the derivative is zero at the guess
+        let f = |x: R64| (x * x - 4.0, x * 2.0);
+        let solution = newton(f, r64(0.0), r64(-1.0), r64(5.0), SolveOptions::default()).unwrap();
+        assert!((solution.root - 2.0).abs() < 1e-14);
+        assert!(solution.iterations < 30);
+    }
+
+    #[test]
+    fn full_range_bracket() {
+        let options = SolveOptions {
+            tolerance: 0.0,
+            max_iterations: 2000,
+        };
+        for &root in [0.0, -3.5, 1e300].iter() {
+            let line = |x: R64| x * 0.5 - root * 0.5;
+            let solutions = [
+                bisection(line, R64::MIN, R64::MAX, options),
+                brent(line, R64::MIN, R64::MAX, options),
+                newton(
+                    |x| (line(x), r64(0.5)),
+                    R64::MIN,
+                    R64::MIN,
+                    R64::MAX,
+                    options,
+                ),
+            ];
+            for solution in solutions.iter() {
+                let solution = solution.unwrap();
+                assert!((solution.root - root).abs() <= root.abs() * 1e-15);
+                assert!(solution.bracket.0 <= root && solution.bracket.1 >= root);
+            }
+        }
+
+        let saturating = |x: R64| x / (x.abs() + 1.0) - 0.5;
+        let solution = brent(saturating, R64::MIN, R64::MAX, options).unwrap();
+        assert!((solution.root - 1.0).abs() < 1e-15);
+        let solution = bisection(saturating, R64::MIN, R64::MAX, options).unwrap();
+        assert!((solution.root - 1.0).abs() < 1e-15);
+        let derivative = |x: R64| {
+            let inverse = r64(1.0) / (x.abs() + 1.0);
+            inverse * inverse
+        };
+        let solution = newton(
+            |x| (saturating(x), derivative(x)),
+            r64(1e300),
+            R64::MIN,
+            R64::MAX,
+            options,
+        );
+        assert!((solution.unwrap().root - 1.0).abs() < 1e-15);
+    }
+}
@@ -0,0 +1,267 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Radix sorting for slices of `NoisyFloat`.
+//!
+//! Since a `NoisyFloat` is never NaN, its bits can be mapped to an unsigned
+//! integer with the same order, and slices can be sorted by these integers
+//! one byte at a time instead of by comparisons.
+//! Whether this is faster than `sort_unstable` depends on the hardware and
+//! on the distribution of the values, which can be checked with the `Sort`
+//! benchmark of this crate.
+//!
+//! ```
+//! use noisy_float::{prelude::*, sort::radix_sort};
+//!
+//! let mut values = vec![r64(2.5), r64(-1.0), r64(0.0), r64(-7.25)];
+//! radix_sort(&mut values);
+//! assert_eq!(values, [r64(-7.25), r64(-1.0), r64(0.0), r64(2.5)]);
+//! ```
+//!
+//! This module requires the `std` feature.
+
+use crate::{sign_mask, FloatChecker, NoisyFloat, RawFloat};
+use core::mem::size_of;
+use num_traits::{PrimInt, Zero};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+use std::vec::Vec;
+
+/// Slices shorter than this are sorted with `sort_unstable` instead.
+const MIN_RADIX_LEN: usize = 64;
+
+/// Sorts the slice, in the order given by `Ord`.
+///
+/// This sort is unstable, i.e. equal elements such as `-0.0` and `+0.0`
+/// may be reordered. It allocates two buffers of the size of the slice.
+pub fn radix_sort<F: RawFloat, C: FloatChecker<F>>(values: &mut [NoisyFloat<F, C>]) {
+    if values.len() < MIN_RADIX_LEN {
+        values.sort_unstable();
+        return;
+    }
+    let mut keys: Vec<F::Bits> = values.iter().map(|value| to_key(*value)).collect();
+    let mut scratch = keys.clone();
+    sort_keys(&mut keys, &mut scratch, size_of::<F::Bits>());
+    for (value, &key) in values.iter_mut().zip(&keys) {
+        *value = from_key(key);
+    }
+}
+
+/// Sorts the slice in parallel, in the order given by `Ord`.
+///
+/// The values are partitioned by their most significant byte,
+/// then the partitions are radix sorted in parallel.
+/// Like `radix_sort`, this sort is unstable.
+///
+/// Requires the `rayon` feature.
+#[cfg(feature = "rayon")]
+pub fn par_radix_sort<F, C>(values: &mut [NoisyFloat<F, C>])
+where
+    F: RawFloat + Send + Sync,
+    F::Bits: Send + Sync,
+    C: FloatChecker<F> + Send + Sync,
+{
+    if values.len() < MIN_RADIX_LEN {
+        values.sort_unstable();
+        return;
+    }
+    let passes = size_of::<F::Bits>();
+    let keys: Vec<F::Bits> = values.par_iter().map(|value| to_key(*value)).collect();
+    let mut partitioned = keys.clone();
+    let mut counts = digit_counts(&keys, passes - 1);
+    to_offsets(&mut counts);
+    let offsets = counts;
+    for &key in &keys {
+        let digit = digit(key, passes - 1);
+        partitioned[counts[digit]] = key;
+        counts[digit] += 1;
+    }
+
+    let mut scratch = keys;
+    let mut partitions = Vec::with_capacity(offsets.len());
+    let (mut rest, mut rest_scratch) = (&mut partitioned[..], &mut scratch[..]);
+    for window in offsets.windows(2) {
+        let len = window[1] - window[0];
+        let (partition, tail) = rest.split_at_mut(len);
+        let (partition_scratch, tail_scratch) = rest_scratch.split_at_mut(len);
+        partitions.push((partition, partition_scratch));
+        rest = tail;
+        rest_scratch = tail_scratch;
+    }
+    partitions.push((rest, rest_scratch));
+    partitions
+        .into_par_iter()
+        .for_each(|(partition, scratch)| sort_keys(partition, scratch, passes - 1));
+
+    values
+        .par_iter_mut()
+        .zip(partitioned.par_iter())
+        .for_each(|(value, &key)| *value = from_key(key));
+}
+
+/// Maps the value to an unsigned integer with the same ordering,
+/// which orders `-0.0` just before `+0.0` so that it can be mapped back.
+#[inline]
+fn to_key<F: RawFloat, C: FloatChecker<F>>(value: NoisyFloat<F, C>) -> F::Bits {
+    let bits = value.value.to_bits();
+    let sign = sign_mask::<F>();
+    if bits & sign != F::Bits::zero() {
+        !bits
+    } else {
+        bits | sign
+    }
+}
+
+#[inline]
+fn from_key<F: RawFloat, C: FloatChecker<F>>(key: F::Bits) -> NoisyFloat<F, C> {
+    let sign = sign_mask::<F>();
+    let bits = if key & sign != F::Bits::zero() {
+        key & !sign
+    } else {
+        !key
+    };
+    // The key was obtained from a valid value by `to_key`.
+    NoisyFloat::new_unchecked(F::from_bits(bits))
+}
+
+#[inline]
+fn digit<B: PrimInt>(key: B, byte: usize) -> usize {
+    ((key >> (8 * byte)) & B::from(0xff).unwrap())
+        .to_usize()
+        .unwrap()
+}
+
+fn digit_counts<B: PrimInt>(keys: &[B], byte: usize) -> [usize; 256] {
+    let mut counts = [0; 256];
+    for &key in keys {
+        counts[digit(key, byte)] += 1;
+    }
+    counts
+}
+
+/// Replaces the counts by the offsets at which each digit starts.
+fn to_offsets(counts: &mut [usize; 256]) {
+    let mut offset = 0;
+    for count in counts.iter_mut() {
+        let len = *count;
+        *count = offset;
+        offset += len;
+    }
+}
+
+/// Sorts the keys by their lowest `bytes` bytes, using most significant
+/// digit radix sort with `scratch` as a buffer of the same length.
+///
+/// Small partitions are sorted with `sort_unstable`, which is faster
+/// than further passes once they fit in the cache.
+fn sort_keys<B: PrimInt>(keys: &mut [B], scratch: &mut [B], bytes: usize) {
+    if keys.len() < MIN_RADIX_LEN || bytes == 0 {
+        keys.sort_unstable();
+        return;
+    }
+    let byte = bytes - 1;
+    let mut counts = digit_counts(keys, byte);
+    if counts.contains(&keys.len()) {
+        // all keys have the same digit, so this pass would not change anything
+        sort_keys(keys, scratch, byte);
+        return;
+    }
+    to_offsets(&mut counts);
+    let offsets = counts;
+    for &key in keys.iter() {
+        let offset = &mut counts[digit(key, byte)];
+        scratch[*offset] = key;
+        *offset += 1;
+    }
+    keys.copy_from_slice(scratch);
+
+    let mut start = 0;
+    for &end in offsets[1..].iter().chain(Some(&keys.len())) {
+        sort_keys(&mut keys[start..end], &mut scratch[start..end], byte);
+        start = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn pseudo_random(len: usize) -> Vec<f64> {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                let value = f64::from_bits(state);
+                if value.is_finite() {
+                    value
+                } else {
+                    (state >> 11) as f64 - (1u64 << 52) as f64
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sorts_like_sort_unstable() {
+        for &len in &[0, 1, 5, MIN_RADIX_LEN, 1000] {
+            let mut values: Vec<R64> = pseudo_random(len).into_iter().map(r64).collect();
+            let mut expected = values.clone();
+            expected.sort_unstable();
+            radix_sort(&mut values);
+            assert_eq!(values, expected);
+        }
+    }
+
+    #[test]
+    fn sorts_special_values() {
+        let mut values = [
+            N32::INFINITY,
+            n32(-0.0),
+            n32(1.0),
+            N32::NEG_INFINITY,
+            n32(0.0),
+            n32(f32::from_bits(1)),
+            n32(-f32::from_bits(1)),
+            N32::MIN,
+            N32::MAX,
+        ]
+        .repeat(20);
+        radix_sort(&mut values);
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(
+            values
+                .iter()
+                .filter(|value| value.is_sign_negative())
+                .count(),
+            80
+        );
+        assert_eq!(values[0], N32::NEG_INFINITY);
+        assert_eq!(values[values.len() - 1], N32::INFINITY);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_sorts_like_sort_unstable() {
+        for &len in &[0, 5, 1000, 100_000] {
+            let mut values: Vec<N64> = pseudo_random(len).into_iter().map(n64).collect();
+            let mut expected = values.clone();
+            expected.sort_unstable();
+            par_radix_sort(&mut values);
+            assert_eq!(values, expected);
+        }
+    }
+}
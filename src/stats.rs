@@ -0,0 +1,959 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Statistics over streams and slices of `NoisyFloat` values.
+//!
+//! ```
+//! use noisy_float::{prelude::*, stats::RunningStats};
+//!
+//! let mut stats = RunningStats::new();
+//! for &value in &[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0] {
+//!     stats.add(r64(value));
+//! }
+//! assert_eq!(stats.count(), 8);
+//! assert_eq!(stats.mean(), Some(r64(5.0)));
+//! assert!((stats.variance().unwrap() - 4.0).abs() < 1e-12);
+//! assert_eq!(stats.max(), Some(r64(9.0)));
+//! ```
+
+use crate::{iter::count_to_float, lerp, FloatChecker, NoisyFloat};
+use core::{fmt, iter::FromIterator, marker::PhantomData};
+use num_traits::float::FloatCore;
+#[cfg(any(feature = "std", feature = "libm"))]
+use num_traits::Float;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// An accumulator of the count, mean, variance, extrema and higher moments
+/// of a stream of values.
+///
+/// The moments are updated with Welford's algorithm, which avoids the
+/// catastrophic cancellation of computing the variance from the sums of the
+/// values and of their squares. Two accumulators, e.g. of the halves of a
+/// data set that were processed in parallel, can be combined with `merge`.
+///
+/// The statistics are returned as `NoisyFloat` values with the checker of the
+/// input. Statistics that are undefined for the values added so far, such as
+/// the mean of no values, or that are rejected by the `FloatChecker`, e.g.
+/// because they overflow, are returned as `None`.
+///
+/// Infinite values, which are valid for e.g. `N64`, are counted separately
+/// from the finite values, so that the result does not depend on the order
+/// in which they are added. The mean of values including infinities of one
+/// sign is that infinity, and the variance and higher moments of values
+/// including infinities are `None`.
+pub struct RunningStats<F: FloatCore, C: FloatChecker<F>> {
+    count: usize,
+    positive_infinities: usize,
+    negative_infinities: usize,
+    mean: F,
+    m2: F,
+    m3: F,
+    m4: F,
+    min: F,
+    max: F,
+    checker: PhantomData<C>,
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> RunningStats<F, C> {
+    /// Constructs an accumulator without any values.
+    #[inline]
+    pub fn new() -> Self {
+        RunningStats {
+            count: 0,
+            positive_infinities: 0,
+            negative_infinities: 0,
+            mean: F::zero(),
+            m2: F::zero(),
+            m3: F::zero(),
+            m4: F::zero(),
+            min: F::infinity(),
+            max: F::neg_infinity(),
+            checker: PhantomData,
+        }
+    }
+
+    /// Adds a value to the statistics.
+    pub fn add(&mut self, value: NoisyFloat<F, C>) {
+        let value = value.raw();
+        if value < self.min {
+            self.min = value;
+        }
+        if value > self.max {
+            self.max = value;
+        }
+        if value == F::infinity() {
+            self.positive_infinities += 1;
+            return;
+        } else if value == F::neg_infinity() {
+            self.negative_infinities += 1;
+            return;
+        }
+        let previous = count_to_float::<F>(self.count);
+        self.count += 1;
+        let n = count_to_float::<F>(self.count);
+
+        let delta = value - self.mean;
+        let delta_n = delta / n;
+        let delta_n2 = delta_n * delta_n;
+        let term = delta * delta_n * previous;
+        let (two, three) = (count_to_float::<F>(2), count_to_float::<F>(3));
+        let (four, six) = (count_to_float::<F>(4), count_to_float::<F>(6));
+
+        self.mean = self.mean + delta_n;
+        self.m4 =
+            self.m4 + term * delta_n2 * (n * n - three * n + three) + six * delta_n2 * self.m2
+                - four * delta_n * self.m3;
+        self.m3 = self.m3 + term * delta_n * (n - two) - three * delta_n * self.m2;
+        self.m2 = self.m2 + term;
+    }
+
+    /// Combines the statistics of the values added to `other` into `self`,
+    /// as if they had been added to `self` directly.
+    pub fn merge(&mut self, other: &Self) {
+        self.positive_infinities += other.positive_infinities;
+        self.negative_infinities += other.negative_infinities;
+        if other.min < self.min {
+            self.min = other.min;
+        }
+        if other.max > self.max {
+            self.max = other.max;
+        }
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            self.count = other.count;
+            self.mean = other.mean;
+            self.m2 = other.m2;
+            self.m3 = other.m3;
+            self.m4 = other.m4;
+            return;
+        }
+        let na = count_to_float::<F>(self.count);
+        let nb = count_to_float::<F>(other.count);
+        self.count += other.count;
+        let n = count_to_float::<F>(self.count);
+        let (three, four, six) = (
+            count_to_float::<F>(3),
+            count_to_float::<F>(4),
+            count_to_float::<F>(6),
+        );
+
+        let delta = other.mean - self.mean;
+        let delta2 = delta * delta;
+        let product = na * nb;
+
+        let m4 = self.m4
+            + other.m4
+            + delta2 * delta2 * product * (na * na - product + nb * nb) / (n * n * n)
+            + six * delta2 * (na * na * other.m2 + nb * nb * self.m2) / (n * n)
+            + four * delta * (na * other.m3 - nb * self.m3) / n;
+        let m3 = self.m3
+            + other.m3
+            + delta2 * delta * product * (na - nb) / (n * n)
+            + three * delta * (na * other.m2 - nb * self.m2) / n;
+        let m2 = self.m2 + other.m2 + delta2 * product / n;
+
+        self.mean = self.mean + delta * nb / n;
+        self.m2 = m2;
+        self.m3 = m3;
+        self.m4 = m4;
+    }
+
+    /// Returns the number of values added so far.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.count + self.positive_infinities + self.negative_infinities
+    }
+
+    /// Returns the mean of the values, or `None` if there are none or if
+    /// they include infinities of both signs.
+    #[inline]
+    pub fn mean(&self) -> Option<NoisyFloat<F, C>> {
+        match (self.positive_infinities > 0, self.negative_infinities > 0) {
+            (true, true) => None,
+            (true, false) => NoisyFloat::try_new(F::infinity()),
+            (false, true) => NoisyFloat::try_new(F::neg_infinity()),
+            (false, false) => self.defined(1, self.mean),
+        }
+    }
+
+    /// Returns the population variance of the values, i.e. the mean of
+    /// the squared deviations from the mean, or `None` if there are none
+    /// or if they include infinities.
+    ///
+    /// Unlike the naive formulas, the result is never negative.
+    #[inline]
+    pub fn variance(&self) -> Option<NoisyFloat<F, C>> {
+        self.defined(1, self.m2 / count_to_float::<F>(self.count))
+    }
+
+    /// Returns the sample variance of the values, with Bessel's correction,
+    /// or `None` if there are fewer than two values or if they include
+    /// infinities.
+    #[inline]
+    pub fn sample_variance(&self) -> Option<NoisyFloat<F, C>> {
+        self.defined(
+            2,
+            self.m2 / count_to_float::<F>(self.count.saturating_sub(1)),
+        )
+    }
+
+    /// Returns the population standard deviation of the values,
+    /// or `None` if there are none or if they include infinities.
+    ///
+    /// Requires either the `std` or the `libm` feature.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub fn std_dev(&self) -> Option<NoisyFloat<F, C>>
+    where
+        F: Float,
+    {
+        self.variance().map(|variance| variance.sqrt())
+    }
+
+    /// Returns the sample standard deviation of the values, or `None`
+    /// if there are fewer than two values or if they include infinities.
+    ///
+    /// Requires either the `std` or the `libm` feature.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub fn sample_std_dev(&self) -> Option<NoisyFloat<F, C>>
+    where
+        F: Float,
+    {
+        self.sample_variance().map(|variance| variance.sqrt())
+    }
+
+    /// Returns the smallest value, or `None` if there are none.
+    #[inline]
+    pub fn min(&self) -> Option<NoisyFloat<F, C>> {
+        if self.count() == 0 {
+            None
+        } else {
+            NoisyFloat::try_new(self.min)
+        }
+    }
+
+    /// Returns the largest value, or `None` if there are none.
+    #[inline]
+    pub fn max(&self) -> Option<NoisyFloat<F, C>> {
+        if self.count() == 0 {
+            None
+        } else {
+            NoisyFloat::try_new(self.max)
+        }
+    }
+
+    /// Returns the population skewness of the values, or `None` if there
+    /// are none, if they are all equal or if they include infinities.
+    ///
+    /// Requires either the `std` or the `libm` feature.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    pub fn skewness(&self) -> Option<NoisyFloat<F, C>>
+    where
+        F: Float,
+    {
+        if self.m2 <= F::zero() {
+            return None;
+        }
+        let n = count_to_float::<F>(self.count);
+        self.defined(
+            1,
+            Float::sqrt(n) * self.m3 / (self.m2 * Float::sqrt(self.m2)),
+        )
+    }
+
+    /// Returns the population excess kurtosis of the values, which is zero
+    /// for a normal distribution, or `None` if there are none, if they
+    /// are all equal or if they include infinities.
+    pub fn kurtosis(&self) -> Option<NoisyFloat<F, C>> {
+        if self.m2 <= F::zero() {
+            return None;
+        }
+        let n = count_to_float::<F>(self.count);
+        self.defined(
+            1,
+            n * self.m4 / (self.m2 * self.m2) - count_to_float::<F>(3),
+        )
+    }
+
+    /// Returns the statistic of the finite values, if there are at least
+    /// `min_count` values and none of them is infinite.
+    #[inline]
+    fn defined(&self, min_count: usize, value: F) -> Option<NoisyFloat<F, C>> {
+        if self.count < min_count || self.count != self.count() {
+            None
+        } else {
+            NoisyFloat::try_new(value)
+        }
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Clone for RunningStats<F, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Copy for RunningStats<F, C> {}
+
+impl<F: FloatCore, C: FloatChecker<F>> Default for RunningStats<F, C> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: FloatCore + fmt::Debug, C: FloatChecker<F>> fmt::Debug for RunningStats<F, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("RunningStats")
+            .field("count", &self.count)
+            .field("positive_infinities", &self.positive_infinities)
+            .field("negative_infinities", &self.negative_infinities)
+            .field("mean", &self.mean)
+            .field("m2", &self.m2)
+            .field("m3", &self.m3)
+            .field("m4", &self.m4)
+            .field("min", &self.min)
+            .field("max", &self.max)
+            .finish()
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Extend<NoisyFloat<F, C>> for RunningStats<F, C> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = NoisyFloat<F, C>>>(&mut self, iter: I) {
+        for value in iter {
+            self.add(value);
+        }
+    }
+}
+
+impl<'a, F: FloatCore, C: FloatChecker<F>> Extend<&'a NoisyFloat<F, C>> for RunningStats<F, C> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'a NoisyFloat<F, C>>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> FromIterator<NoisyFloat<F, C>> for RunningStats<F, C> {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = NoisyFloat<F, C>>>(iter: I) -> Self {
+        let mut stats = Self::new();
+        stats.extend(iter);
+        stats
+    }
+}
+
+impl<'a, F: FloatCore, C: FloatChecker<F>> FromIterator<&'a NoisyFloat<F, C>>
+    for RunningStats<F, C>
+{
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = &'a NoisyFloat<F, C>>>(iter: I) -> Self {
+        iter.into_iter().copied().collect()
+    }
+}
+
+/// The method used by `quantile` and `percentiles` to pick a value between
+/// the two values surrounding the position of the quantile.
+///
+/// For `n` sorted values and a quantile `q`, the position is `(n - 1) * q`,
+/// counting from zero, except for `NearestRank`. The names follow numpy, and
+/// `Linear` and `NearestRank` are definitions 7 and 1 of Hyndman and Fan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum QuantileMethod {
+    /// Linearly interpolates between the surrounding values.
+    /// This is the default of R and numpy.
+    Linear,
+    /// The smallest value such that at least a fraction `q` of the values
+    /// are less than or equal to it.
+    NearestRank,
+    /// The lower of the surrounding values.
+    Lower,
+    /// The higher of the surrounding values.
+    Higher,
+    /// The nearest of the surrounding values, or the one with an even index
+    /// when the position is exactly between them.
+    Nearest,
+    /// The mean of the surrounding values.
+    Midpoint,
+}
+
+impl Default for QuantileMethod {
+    #[inline]
+    fn default() -> Self {
+        QuantileMethod::Linear
+    }
+}
+
+impl QuantileMethod {
+    /// Returns the indices of the surrounding values in `len` sorted values,
+    /// and the weight of the higher one, or `None` if `q` is not in `[0, 1]`.
+    fn position<F: FloatCore>(self, len: usize, q: F) -> Option<(usize, usize, F)> {
+        if !(q >= F::zero() && q <= F::one()) || len == 0 {
+            return None;
+        }
+        let last = len - 1;
+        if self == QuantileMethod::NearestRank {
+            let rank = (count_to_float::<F>(len) * q)
+                .ceil()
+                .to_usize()
+                .unwrap_or(len);
+            let index = rank.max(1).min(len) - 1;
+            return Some((index, index, F::zero()));
+        }
+        let h = count_to_float::<F>(last) * q;
+        let lower = h.floor().to_usize().unwrap_or(last).min(last);
+        let higher = (lower + 1).min(last);
+        let fraction = h - count_to_float::<F>(lower);
+        let half = F::one() / (F::one() + F::one());
+        Some(match self {
+            QuantileMethod::Linear => (lower, higher, fraction),
+            QuantileMethod::Lower => (lower, lower, F::zero()),
+            QuantileMethod::Higher if fraction > F::zero() => (higher, higher, F::zero()),
+            QuantileMethod::Higher => (lower, lower, F::zero()),
+            QuantileMethod::Nearest if fraction > half || (fraction == half && lower % 2 == 1) => {
+                (higher, higher, F::zero())
+            }
+            QuantileMethod::Nearest => (lower, lower, F::zero()),
+            QuantileMethod::Midpoint => (lower, higher, half),
+            QuantileMethod::NearestRank => unreachable!(),
+        })
+    }
+}
+
+/// Returns the median of the values, or `None` if the slice is empty.
+///
+/// The median of an even number of values is the mean of the middle two.
+/// The values are partially reordered in linear time, as by
+/// `select_nth_unstable`, instead of being sorted.
+///
+/// ```
+/// use noisy_float::{prelude::*, stats::median};
+///
+/// let mut values = [r64(3.0), r64(-1.0), r64(8.0), r64(2.0)];
+/// assert_eq!(median(&mut values), Some(r64(2.5)));
+/// ```
+#[inline]
+pub fn median<F: FloatCore, C: FloatChecker<F>>(
+    values: &mut [NoisyFloat<F, C>],
+) -> Option<NoisyFloat<F, C>> {
+    quantile(
+        values,
+        F::one() / (F::one() + F::one()),
+        QuantileMethod::Linear,
+    )
+}
+
+/// Returns the quantile `q` of the values, where `q` is between 0 and 1,
+/// or `None` if the slice is empty or `q` is out of range.
+///
+/// The values are partially reordered in linear time, as by
+/// `select_nth_unstable`, instead of being sorted.
+///
+/// ```
+/// use noisy_float::{prelude::*, stats::{quantile, QuantileMethod}};
+///
+/// let mut values = [r64(4.0), r64(1.0), r64(3.0), r64(2.0)];
+/// assert_eq!(quantile(&mut values, 0.25, QuantileMethod::Linear), Some(r64(1.75)));
+/// assert_eq!(quantile(&mut values, 0.25, QuantileMethod::NearestRank), Some(r64(1.0)));
+/// assert_eq!(quantile(&mut values, 1.5, QuantileMethod::Linear), None);
+/// ```
+pub fn quantile<F: FloatCore, C: FloatChecker<F>>(
+    values: &mut [NoisyFloat<F, C>],
+    q: F,
+    method: QuantileMethod,
+) -> Option<NoisyFloat<F, C>> {
+    let (lower, higher, weight) = method.position(values.len(), q)?;
+    let (_, &mut lower_value, rest) = values.select_nth_unstable(lower);
+    if higher == lower {
+        return Some(lower_value);
+    }
+    // the higher value is the smallest of the values after the lower one
+    let higher_value = *rest.iter().min()?;
+    Some(NoisyFloat::new(lerp(
+        lower_value.raw(),
+        higher_value.raw(),
+        weight,
+    )))
+}
+
+/// Returns the percentiles of the values, i.e. their quantiles at the given
+/// percentages between 0 and 100, or `None` if the slice is empty or a
+/// percentage is out of range.
+///
+/// The values are sorted, so that each percentile takes constant time.
+///
+/// Requires the `std` feature.
+///
+/// ```
+/// use noisy_float::{prelude::*, stats::{percentiles, QuantileMethod}};
+///
+/// let mut values: Vec<R64> = (1..=101).rev().map(|i| r64(i as f64)).collect();
+/// let quartiles = percentiles(&mut values, &[25.0, 50.0, 75.0], QuantileMethod::Linear);
+/// assert_eq!(quartiles, Some(vec![r64(26.0), r64(51.0), r64(76.0)]));
+/// ```
+#[cfg(feature = "std")]
+pub fn percentiles<F: FloatCore, C: FloatChecker<F>>(
+    values: &mut [NoisyFloat<F, C>],
+    percentages: &[F],
+    method: QuantileMethod,
+) -> Option<Vec<NoisyFloat<F, C>>> {
+    let hundred = count_to_float::<F>(100);
+    let positions = percentages
+        .iter()
+        .map(|&percentage| method.position(values.len(), percentage / hundred))
+        .collect::<Option<Vec<_>>>()?;
+    values.sort_unstable();
+    Some(
+        positions
+            .into_iter()
+            .map(|(lower, higher, weight)| {
+                NoisyFloat::new(lerp(values[lower].raw(), values[higher].raw(), weight))
+            })
+            .collect(),
+    )
+}
+
+/// An estimate of a quantile of a stream of values in constant memory,
+/// using the P² algorithm of Jain and Chlamtac.
+///
+/// The estimate is kept in five markers, whose heights are adjusted with
+/// piecewise-parabolic interpolation as values are added. Up to five values,
+/// the quantile is exact, as with `QuantileMethod::Linear`.
+///
+/// ```
+/// use noisy_float::{prelude::*, stats::P2Quantile};
+///
+/// let mut median = P2Quantile::new(0.5);
+/// for i in 0..1001 {
+///     median.add(r64((i * 37 % 1001) as f64));
+/// }
+/// assert!((median.estimate().unwrap() - 500.0).abs() < 5.0);
+/// ```
+pub struct P2Quantile<F: FloatCore, C: FloatChecker<F>> {
+    q: F,
+    count: usize,
+    heights: [F; 5],
+    positions: [usize; 5],
+    desired: [F; 5],
+    increments: [F; 5],
+    checker: PhantomData<C>,
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> P2Quantile<F, C> {
+    /// Constructs an estimator of the quantile `q`, which must be between 0 and 1.
+    ///
+    /// Panics if `q` is out of range.
+    #[inline]
+    pub fn new(q: F) -> Self {
+        Self::try_new(q).expect("quantile out of range")
+    }
+
+    /// Tries to construct an estimator of the quantile `q`,
+    /// returning `None` if `q` is not between 0 and 1.
+    pub fn try_new(q: F) -> Option<Self> {
+        if !(q >= F::zero() && q <= F::one()) {
+            return None;
+        }
+        let two = F::one() + F::one();
+        let four = two + two;
+        Some(P2Quantile {
+            q,
+            count: 0,
+            heights: [F::zero(); 5],
+            positions: [0, 1, 2, 3, 4],
+            desired: [F::zero(), two * q, four * q, two + two * q, four],
+            increments: [F::zero(), q / two, q, (F::one() + q) / two, F::one()],
+            checker: PhantomData,
+        })
+    }
+
+    /// Returns the quantile being estimated.
+    #[inline]
+    pub fn quantile(&self) -> F {
+        self.q
+    }
+
+    /// Returns the number of values added so far.
+    #[inline]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Adds a value to the stream.
+    pub fn add(&mut self, value: NoisyFloat<F, C>) {
+        let value = value.raw();
+        if self.count < 5 {
+            // keep the first values sorted
+            let mut index = self.count;
+            while index > 0 && self.heights[index - 1] > value {
+                self.heights[index] = self.heights[index - 1];
+                index -= 1;
+            }
+            self.heights[index] = value;
+            self.count += 1;
+            return;
+        }
+        self.count += 1;
+
+        let cell = if value < self.heights[0] {
+            self.heights[0] = value;
+            0
+        } else if value >= self.heights[4] {
+            self.heights[4] = value;
+            3
+        } else {
+            (1..4).find(|&i| value < self.heights[i]).unwrap_or(4) - 1
+        };
+        for position in &mut self.positions[cell + 1..] {
+            *position += 1;
+        }
+        for (desired, &increment) in self.desired.iter_mut().zip(&self.increments) {
+            *desired = *desired + increment;
+        }
+
+        for i in 1..4 {
+            let offset = self.desired[i] - count_to_float::<F>(self.positions[i]);
+            let up = offset >= F::one() && self.positions[i + 1] - self.positions[i] > 1;
+            let down = offset <= -F::one() && self.positions[i] - self.positions[i - 1] > 1;
+            if up || down {
+                let height = self.parabolic(i, up);
+                self.heights[i] = if self.heights[i - 1] < height && height < self.heights[i + 1] {
+                    height
+                } else {
+                    self.linear(i, up)
+                };
+                if up {
+                    self.positions[i] += 1;
+                } else {
+                    self.positions[i] -= 1;
+                }
+            }
+        }
+    }
+
+    /// The height of marker `i` moved by one position, from the parabola
+    /// through it and its neighbours.
+    fn parabolic(&self, i: usize, up: bool) -> F {
+        let d = if up { F::one() } else { -F::one() };
+        let (q, n) = (&self.heights, &self.positions);
+        let position = |j: usize| count_to_float::<F>(n[j]);
+        let (left, center, right) = (position(i - 1), position(i), position(i + 1));
+        q[i] + d / (right - left)
+            * ((center - left + d) * (q[i + 1] - q[i]) / (right - center)
+                + (right - center - d) * (q[i] - q[i - 1]) / (center - left))
+    }
+
+    /// The height of marker `i` moved by one position, from the line
+    /// through it and the neighbour in the direction of the move.
+    fn linear(&self, i: usize, up: bool) -> F {
+        let j = if up { i + 1 } else { i - 1 };
+        let (q, n) = (&self.heights, &self.positions);
+        let distance = count_to_float::<F>(n[j]) - count_to_float::<F>(n[i]);
+        let d = if up { F::one() } else { -F::one() };
+        q[i] + d * (q[j] - q[i]) / distance
+    }
+
+    /// Returns the estimate of the quantile, or `None` if no values were added.
+    pub fn estimate(&self) -> Option<NoisyFloat<F, C>> {
+        if self.count > 5 {
+            return Some(NoisyFloat::new(self.heights[2]));
+        }
+        let (lower, higher, weight) = QuantileMethod::Linear.position(self.count, self.q)?;
+        let (lower, higher) = (self.heights[lower], self.heights[higher]);
+        Some(NoisyFloat::new(lerp(lower, higher, weight)))
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Clone for P2Quantile<F, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Copy for P2Quantile<F, C> {}
+
+impl<F: FloatCore + fmt::Debug, C: FloatChecker<F>> fmt::Debug for P2Quantile<F, C> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        f.debug_struct("P2Quantile")
+            .field("q", &self.q)
+            .field("count", &self.count)
+            .field("heights", &self.heights)
+            .field("positions", &self.positions)
+            .finish()
+    }
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> Extend<NoisyFloat<F, C>> for P2Quantile<F, C> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = NoisyFloat<F, C>>>(&mut self, iter: I) {
+        for value in iter {
+            self.add(value);
+        }
+    }
+}
+
+impl<'a, F: FloatCore, C: FloatChecker<F>> Extend<&'a NoisyFloat<F, C>> for P2Quantile<F, C> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'a NoisyFloat<F, C>>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    fn assert_close(actual: Option<R64>, expected: f64) {
+        let actual = actual.unwrap().raw();
+        assert!(
+            (actual - expected).abs() <= 1e-9 * expected.abs().max(1.0),
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn empty() {
+        let stats = RunningStats::<f64, crate::checkers::FiniteChecker>::new();
+        assert_eq!(stats.count(), 0);
+        assert_eq!(stats.mean(), None);
+        assert_eq!(stats.variance(), None);
+        assert_eq!(stats.min(), None);
+        assert_eq!(stats.max(), None);
+        assert_eq!(stats.kurtosis(), None);
+
+        let stats: RunningStats<f64, _> = Some(r64(3.0)).into_iter().collect();
+        assert_eq!(stats.mean(), Some(r64(3.0)));
+        assert_eq!(stats.variance(), Some(r64(0.0)));
+        assert_eq!(stats.sample_variance(), None);
+        assert_eq!(stats.kurtosis(), None);
+    }
+
+    #[test]
+    fn moments() {
+        let values = [
+            r64(2.0),
+            r64(4.0),
+            r64(4.0),
+            r64(4.0),
+            r64(5.0),
+            r64(5.0),
+            r64(7.0),
+            r64(9.0),
+        ];
+        let stats: RunningStats<f64, _> = values.iter().collect();
+        assert_eq!(stats.count(), 8);
+        assert_close(stats.mean(), 5.0);
+        assert_close(stats.variance(), 4.0);
+        assert_close(stats.sample_variance(), 32.0 / 7.0);
+        assert_eq!(stats.min(), Some(r64(2.0)));
+        assert_eq!(stats.max(), Some(r64(9.0)));
+        // the central moments are 4, 5.25 and 44.5
+        assert_close(stats.kurtosis(), 44.5 / 16.0 - 3.0);
+        #[cfg(any(feature = "std", feature = "libm"))]
+        {
+            assert_close(stats.std_dev(), 2.0);
+            assert_close(stats.skewness(), 5.25 / 8.0);
+        }
+    }
+
+    #[test]
+    fn no_cancellation() {
+        let offset = 1.0e9;
+        let stats: RunningStats<f64, _> = [4.0, 7.0, 13.0, 16.0]
+            .iter()
+            .map(|&value| r64(offset + value))
+            .collect();
+        assert_close(stats.variance(), 22.5);
+    }
+
+    #[test]
+    fn merge() {
+        let values: [R64; 9] = [1.0, -3.5, 2.25, 8.0, 0.5, 0.5, -1.0, 12.0, 3.0].map(r64);
+        let all: RunningStats<f64, _> = values.iter().collect();
+        for split in 0..=values.len() {
+            let mut left: RunningStats<f64, _> = values[..split].iter().collect();
+            let right: RunningStats<f64, _> = values[split..].iter().collect();
+            left.merge(&right);
+            assert_eq!(left.count(), all.count());
+            assert_close(left.mean(), all.mean().unwrap().raw());
+            assert_close(left.variance(), all.variance().unwrap().raw());
+            assert_close(left.kurtosis(), all.kurtosis().unwrap().raw());
+            assert_eq!(left.min(), all.min());
+            assert_eq!(left.max(), all.max());
+            #[cfg(any(feature = "std", feature = "libm"))]
+            assert_close(left.skewness(), all.skewness().unwrap().raw());
+        }
+    }
+
+    #[test]
+    fn infinities() {
+        let values = [N64::INFINITY, n64(1.0), n64(2.0), N64::INFINITY];
+        for first in 0..values.len() {
+            // the same statistics regardless of the order of the values
+            let stats: RunningStats<f64, _> = values[first..]
+                .iter()
+                .chain(values[..first].iter())
+                .collect();
+            assert_eq!(stats.count(), 4);
+            assert_eq!(stats.mean(), Some(N64::INFINITY));
+            assert_eq!(stats.variance(), None);
+            assert_eq!(stats.sample_variance(), None);
+            assert_eq!(stats.kurtosis(), None);
+            assert_eq!(stats.min(), Some(n64(1.0)));
+            assert_eq!(stats.max(), Some(N64::INFINITY));
+            #[cfg(any(feature = "std", feature = "libm"))]
+            {
+                assert_eq!(stats.std_dev(), None);
+                assert_eq!(stats.skewness(), None);
+            }
+        }
+
+        let mut stats: RunningStats<f64, _> = Some(N64::NEG_INFINITY).into_iter().collect();
+        assert_eq!(stats.mean(), Some(N64::NEG_INFINITY));
+        assert_eq!(stats.variance(), None);
+        stats.add(N64::INFINITY);
+        assert_eq!(stats.mean(), None);
+        assert_eq!(stats.min(), Some(N64::NEG_INFINITY));
+
+        let mut left: RunningStats<f64, _> = [n64(1.0), n64(3.0)].iter().collect();
+        let right: RunningStats<f64, _> = [N64::NEG_INFINITY].iter().collect();
+        left.merge(&right);
+        assert_eq!(left.count(), 3);
+        assert_eq!(left.mean(), Some(N64::NEG_INFINITY));
+        assert_eq!(left.variance(), None);
+        assert_eq!(left.max(), Some(n64(3.0)));
+    }
+
+    #[test]
+    fn overflow() {
+        let stats: RunningStats<f64, _> = [R64::MAX, R64::MIN].iter().collect();
+        assert_eq!(stats.count(), 2);
+        assert_eq!(stats.variance(), None);
+        assert_eq!(stats.min(), Some(R64::MIN));
+    }
+
+    #[test]
+    fn quantile_methods() {
+        use QuantileMethod::*;
+        let values = [r64(40.0), r64(10.0), r64(30.0), r64(20.0)];
+        let cases = [
+            (Linear, 0.5, 25.0),
+            (Linear, 0.1, 13.0),
+            (NearestRank, 0.5, 20.0),
+            (NearestRank, 0.51, 30.0),
+            (NearestRank, 0.0, 10.0),
+            (Lower, 0.5, 20.0),
+            (Higher, 0.5, 30.0),
+            (Higher, 2.0 / 3.0, 30.0),
+            (Nearest, 0.5, 30.0),
+            (Nearest, 1.0 / 6.0, 10.0),
+            (Nearest, 0.6, 30.0),
+            (Midpoint, 0.1, 15.0),
+            (Midpoint, 1.0, 40.0),
+        ];
+        for &(method, q, expected) in &cases {
+            let mut values = values;
+            assert_eq!(
+                quantile(&mut values, q, method),
+                Some(r64(expected)),
+                "{:?} {}",
+                method,
+                q
+            );
+        }
+        let mut values = values;
+        assert_eq!(quantile(&mut values, -0.1, Linear), None);
+        assert_eq!(quantile(&mut values, f64::NAN, Linear), None);
+        assert_eq!(quantile(&mut [] as &mut [R64], 0.5, Linear), None);
+        assert_eq!(
+            quantile(&mut [r32(-f32::MAX), r32(f32::MAX)], 0.5, Linear),
+            Some(r32(0.0))
+        );
+    }
+
+    #[test]
+    fn median_of_slices() {
+        assert_eq!(median(&mut [] as &mut [R64]), None);
+        assert_eq!(median(&mut [r64(2.0)]), Some(r64(2.0)));
+        assert_eq!(median(&mut [r64(5.0), r64(-1.0), r64(2.0)]), Some(r64(2.0)));
+        let mut values: [R64; 6] = [6.0, 1.0, 5.0, 2.0, 4.0, 3.0].map(r64);
+        assert_eq!(median(&mut values), Some(r64(3.5)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn percentiles_of_slices() {
+        let mut values: Vec<R64> = (0..=10).rev().map(|i| r64(i as f64)).collect();
+        assert_eq!(
+            percentiles(&mut values, &[0.0, 15.0, 100.0], QuantileMethod::Linear),
+            Some(std::vec![r64(0.0), r64(1.5), r64(10.0)])
+        );
+        assert_eq!(
+            percentiles(&mut values, &[50.0, 101.0], QuantileMethod::Linear),
+            None
+        );
+        assert_eq!(
+            percentiles(&mut [] as &mut [R64], &[50.0], QuantileMethod::Linear),
+            None
+        );
+    }
+
+    #[test]
+    fn p2_quantile() {
+        assert!(P2Quantile::<f64, crate::checkers::FiniteChecker>::try_new(1.5).is_none());
+
+        let mut estimate = P2Quantile::new(0.5);
+        assert_eq!(estimate.estimate(), None);
+        estimate.extend(&[r64(3.0), r64(1.0)]);
+        assert_eq!(estimate.estimate(), Some(r64(2.0)));
+
+        // exact up to and including five values
+        let mut estimate = P2Quantile::new(0.9);
+        for (count, value) in [r64(5.0), r64(1.0), r64(4.0), r64(2.0), r64(3.0)]
+            .iter()
+            .enumerate()
+        {
+            estimate.add(*value);
+            let mut values = [r64(5.0), r64(1.0), r64(4.0), r64(2.0), r64(3.0)];
+            let exact = quantile(&mut values[..=count], 0.9, QuantileMethod::Linear);
+            assert_eq!(estimate.estimate(), exact);
+        }
+        assert_eq!(estimate.estimate(), Some(r64(4.6)));
+
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut values = [r64(0.0); 10_000];
+        for value in values.iter_mut() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            *value = r64((state >> 11) as f64 / (1u64 << 53) as f64);
+        }
+        for &q in &[0.05, 0.5, 0.9] {
+            let mut estimate = P2Quantile::new(q);
+            estimate.extend(&values);
+            assert_eq!(estimate.count(), values.len());
+            let exact = quantile(&mut values.clone(), q, QuantileMethod::Linear).unwrap();
+            assert!((estimate.estimate().unwrap() - exact).abs() < 0.01);
+        }
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Strategies for generating `NoisyFloat` values with `proptest`.
+//!
+//! Uniformly random floats rarely hit the values where float code tends to
+//! break, so these strategies generate edge values a quarter of the time:
+//! `±0`, the smallest and largest subnormal values, `min_positive_value`,
+//! `±1`, `max_value`, infinity, and the values next to the boundaries of
+//! the checker or range, as far as they are valid.
+//!
+//! `NoisyFloat<f32, C>` and `NoisyFloat<f64, C>` implement `Arbitrary` with
+//! the same strategy as `any_n64` and friends, for any checker.
+//! The values are drawn from the whole float domain and filtered by the
+//! checker, so for a custom checker that only accepts a narrow range of
+//! values, proptest gives up with "Too many local rejects". Use `f32_in` or
+//! `f64_in` with explicit bounds for such checkers instead.
+//!
+//! ```
+//! use noisy_float::{prelude::*, strategy::{any_r64, r64_in}};
+//! use proptest::prelude::*;
+//!
+//! proptest!(|(x in any_r64())| {
+//!     prop_assert!(x.abs() >= 0.0);
+//! });
+//!
+//! proptest!(|(x in any::<N64>(), y in r64_in(r64(-1.0)..=r64(1.0)))| {
+//!     prop_assert!(x.max(N64::from(y)) >= y.raw());
+//!     prop_assert_eq!(y.clamp(r64(-1.0), r64(1.0)), y);
+//! });
+//! ```
+//!
+//! ```
+//! use noisy_float::{FloatChecker, NoisyFloat, strategy::f64_in};
+//! use proptest::prelude::*;
+//!
+//! struct UnitChecker;
+//!
+//! impl FloatChecker<f64> for UnitChecker {
+//!     fn check(value: f64) -> bool {
+//!         (0.0..=1.0).contains(&value)
+//!     }
+//!
+//!     fn assert(value: f64) {
+//!         debug_assert!(Self::check(value), "unexpected value");
+//!     }
+//! }
+//!
+//! type Unit = NoisyFloat<f64, UnitChecker>;
+//!
+//! let bounds = Unit::new(0.0)..=Unit::new(1.0);
+//! proptest!(|(x in f64_in(bounds.clone()))| {
+//!     prop_assert!(x.raw() <= 1.0);
+//! });
+//! ```
+
+use crate::{
+    types::{N32, N64, R32, R64},
+    FloatChecker, NoisyFloat, RawFloat,
+};
+use core::{
+    fmt,
+    ops::{Bound, RangeBounds},
+};
+use num_traits::One;
+use proptest::{
+    arbitrary::{any, Arbitrary},
+    num, prop_oneof,
+    sample::select,
+    strategy::{BoxedStrategy, Strategy},
+};
+use std::vec::Vec;
+
+/// Returns the edge values accepted by the checker, together with the
+/// values next to the boundaries of the checker.
+fn edge_values<F: RawFloat, C: FloatChecker<F>>() -> Vec<NoisyFloat<F, C>> {
+    let one = F::Bits::one();
+    let min_positive = F::min_positive_value();
+    let candidates = [
+        F::zero(),
+        F::from_bits(one),
+        F::from_bits(min_positive.to_bits() - one),
+        min_positive,
+        F::one(),
+        F::max_value(),
+        F::infinity(),
+    ];
+    let mut edges = Vec::new();
+    for &value in candidates.iter() {
+        for &value in [value, -value].iter() {
+            if let Some(value) = NoisyFloat::try_new(value) {
+                push_unique(&mut edges, value);
+            }
+        }
+    }
+    for index in 0..edges.len() {
+        let value = edges[index];
+        if value.next_up().is_err() {
+            if let Ok(below) = value.next_down() {
+                push_unique(&mut edges, below);
+            }
+        }
+        if value.next_down().is_err() {
+            if let Ok(above) = value.next_up() {
+                push_unique(&mut edges, above);
+            }
+        }
+    }
+    edges
+}
+
+/// Returns the edge values within `[low, high]`, together with the ends of
+/// the range and the values next to them.
+fn edge_values_in<F: RawFloat, C: FloatChecker<F>>(
+    low: NoisyFloat<F, C>,
+    high: NoisyFloat<F, C>,
+) -> Vec<NoisyFloat<F, C>> {
+    let mut edges = Vec::new();
+    let ends = [Ok(low), low.next_up(), Ok(high), high.next_down()];
+    for value in ends.iter().filter_map(|value| value.ok()) {
+        if low <= value && value <= high {
+            push_unique(&mut edges, value);
+        }
+    }
+    for value in edge_values::<F, C>() {
+        if low <= value && value <= high {
+            push_unique(&mut edges, value);
+        }
+    }
+    edges
+}
+
+/// Pushes the value unless it is already present, distinguishing `-0.0`
+/// from `+0.0`.
+#[inline]
+fn push_unique<F: RawFloat, C: FloatChecker<F>>(
+    values: &mut Vec<NoisyFloat<F, C>>,
+    value: NoisyFloat<F, C>,
+) {
+    if values.iter().all(|v| v.to_bits() != value.to_bits()) {
+        values.push(value);
+    }
+}
+
+/// Returns the inclusive ends of the range, where unbounded ends are
+/// replaced by the most negative and most positive finite values.
+///
+/// Panics if the range is empty.
+fn inclusive_ends<F: RawFloat, C: FloatChecker<F>>(
+    range: &impl RangeBounds<NoisyFloat<F, C>>,
+) -> (NoisyFloat<F, C>, NoisyFloat<F, C>) {
+    let low = match range.start_bound() {
+        Bound::Included(&low) => Some(low),
+        Bound::Excluded(&low) => low.next_up().ok(),
+        Bound::Unbounded => NoisyFloat::try_new(-F::max_value()),
+    };
+    let high = match range.end_bound() {
+        Bound::Included(&high) => Some(high),
+        Bound::Excluded(&high) => high.next_down().ok(),
+        Bound::Unbounded => NoisyFloat::try_new(F::max_value()),
+    };
+    match (low, high) {
+        (Some(low), Some(high)) if low <= high => (low, high),
+        _ => panic!("empty range"),
+    }
+}
+
+/// Combines a strategy with edge values, which are chosen a quarter of the
+/// time.
+fn with_edges<T: Clone + fmt::Debug + 'static>(
+    strategy: impl Strategy<Value = T> + 'static,
+    edges: Vec<T>,
+) -> BoxedStrategy<T> {
+    if edges.is_empty() {
+        strategy.boxed()
+    } else {
+        prop_oneof![1 => select(edges), 3 => strategy].boxed()
+    }
+}
+
+macro_rules! impl_strategies {
+    ($f:ident, $any_n:ident, $any_r:ident, $f_in:ident, $r_in:ident, $n:ty, $r:ty) => {
+        impl<C: FloatChecker<$f> + 'static> Arbitrary for NoisyFloat<$f, C> {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<Self>;
+
+            /// Filters the whole float domain by the checker, so this is not
+            #[doc = concat!("suitable for narrow checkers; use `", stringify!($f_in), "` instead.")]
+            fn arbitrary_with(_: ()) -> Self::Strategy {
+                use num::$f::{INFINITE, NEGATIVE, NORMAL, POSITIVE, SUBNORMAL, ZERO};
+                let values = (POSITIVE | NEGATIVE | NORMAL | SUBNORMAL | ZERO | INFINITE)
+                    .prop_filter_map("value rejected by the checker", NoisyFloat::try_new);
+                with_edges(values, edge_values())
+            }
+        }
+
+        #[doc = concat!("Returns a strategy generating any `", stringify!($n), "` value.")]
+        pub fn $any_n() -> BoxedStrategy<$n> {
+            any::<$n>()
+        }
+
+        #[doc = concat!("Returns a strategy generating any `", stringify!($r), "` value.")]
+        pub fn $any_r() -> BoxedStrategy<$r> {
+            any::<$r>()
+        }
+
+        #[doc = concat!("Returns a strategy generating `NoisyFloat<", stringify!($f), ", C>` values within the range,")]
+        /// for any checker.
+        ///
+        /// Values within the range that are rejected by the checker are
+        /// skipped. Unbounded ends of the range are limited by the finite
+        /// values, which the checker must accept.
+        /// Panics if the range is empty.
+        pub fn $f_in<C: FloatChecker<$f> + 'static>(
+            range: impl RangeBounds<NoisyFloat<$f, C>>,
+        ) -> BoxedStrategy<NoisyFloat<$f, C>> {
+            let (low, high) = inclusive_ends(&range);
+            let (low_raw, high_raw) = (low.raw(), high.raw());
+            let values = if (high_raw - low_raw).is_finite() {
+                (low_raw..=high_raw).boxed()
+            } else {
+                // the width of the range overflows, so sample both signs separately
+                prop_oneof![low_raw..=0.0, 0.0..=high_raw].boxed()
+            };
+            let values =
+                values.prop_filter_map("value rejected by the checker", NoisyFloat::try_new);
+            with_edges(values, edge_values_in(low, high))
+        }
+
+        #[doc = concat!("Returns a strategy generating `", stringify!($r), "` values within the range.")]
+        ///
+        /// Unbounded ends of the range are limited by the finite values.
+        /// Panics if the range is empty.
+        pub fn $r_in(range: impl RangeBounds<$r>) -> BoxedStrategy<$r> {
+            $f_in(range)
+        }
+    };
+}
+
+impl_strategies!(f32, any_n32, any_r32, f32_in, r32_in, N32, R32);
+impl_strategies!(f64, any_n64, any_r64, f64_in, r64_in, N64, R64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+    use proptest::{
+        prop_assert,
+        strategy::ValueTree,
+        test_runner::{Config, TestRunner},
+    };
+
+    fn samples<T>(strategy: impl Strategy<Value = T>) -> Vec<T> {
+        let mut runner = TestRunner::deterministic();
+        (0..2000)
+            .map(|_| strategy.new_tree(&mut runner).unwrap().current())
+            .collect()
+    }
+
+    #[test]
+    fn edges() {
+        let values = samples(any_r64());
+        for &edge in [0.0, -0.0, 5e-324, f64::MIN_POSITIVE, f64::MAX, -f64::MAX].iter() {
+            assert!(
+                values.iter().any(|v| v.to_bits() == edge.to_bits()),
+                "{}",
+                edge
+            );
+        }
+        let below_max = f64::from_bits(f64::MAX.to_bits() - 1);
+        assert!(values.contains(&r64(below_max)));
+        assert!(values.iter().any(|v| v.abs() > 1e-300 && v.abs() < 1e300));
+
+        let values = samples(any_n32());
+        assert!(values.contains(&N32::INFINITY));
+        assert!(values.contains(&N32::NEG_INFINITY));
+        assert!(values.contains(&n32(f32::MAX)));
+    }
+
+    #[test]
+    fn ranges() {
+        let values = samples(r64_in(r64(-1.0)..r64(2.0)));
+        assert!(values.iter().all(|v| (r64(-1.0)..r64(2.0)).contains(v)));
+        assert!(values.contains(&r64(-1.0)));
+        assert!(values.contains(&r64(2.0).next_down().unwrap()));
+        assert!(values.contains(&r64(-1.0).next_up().unwrap()));
+        assert!(values.iter().any(|v| v.to_bits() == (-0.0f64).to_bits()));
+
+        let values = samples(r32_in(r32(1.0)..));
+        assert!(values.iter().all(|&v| v >= 1.0));
+        assert!(values.contains(&R32::MAX));
+
+        let values = samples(r64_in(..));
+        assert!(values.iter().any(|&v| v < -1.0));
+        assert!(values.iter().any(|&v| v > 1.0));
+
+        let values = samples(r64_in(r64(3.0)..=r64(3.0)));
+        assert!(values.iter().all(|&v| v == 3.0));
+    }
+
+    struct UnitChecker;
+
+    impl FloatChecker<f32> for UnitChecker {
+        fn check(value: f32) -> bool {
+            (0.0..=1.0).contains(&value)
+        }
+
+        fn assert(value: f32) {
+            debug_assert!(Self::check(value), "unexpected value");
+        }
+    }
+
+    #[test]
+    fn narrow_checker() {
+        let low = NoisyFloat::<f32, UnitChecker>::new(0.0);
+        let high = NoisyFloat::new(1.0);
+        let values = samples(f32_in(low..=high));
+        assert!(values.iter().all(|v| (0.0..=1.0).contains(&v.raw())));
+        assert!(values.contains(&low));
+        assert!(values.contains(&high));
+        assert!(values.iter().any(|v| v.raw() > 0.0 && v.raw() < 1.0));
+
+        let values = samples(f64_in(n64(-1.0)..));
+        assert!(values.iter().all(|&v| v >= -1.0 && v.is_finite()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_range() {
+        let _ = r64_in(r64(1.0)..r64(1.0));
+    }
+
+    #[test]
+    fn arbitrary() {
+        let mut runner = TestRunner::new(Config::with_cases(500));
+        runner
+            .run(&(any::<N64>(), any::<R32>()), |(n, r)| {
+                prop_assert!(!n.raw().is_nan());
+                prop_assert!(r.is_finite());
+                Ok(())
+            })
+            .unwrap();
+    }
+}
@@ -0,0 +1,457 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Accurate summation of floating point numbers.
+//!
+//! Summing with `Iterator::sum` accumulates a rounding error with every
+//! addition. The accumulators and iterator methods in this module reduce
+//! this error, and validate the result with the `FloatChecker`.
+//! The accumulators also handle a running sum that temporarily exceeds the
+//! range of the float type, so that e.g. `MAX + MAX - MAX` is `MAX`.
+//!
+//! Values are accumulated in the float type of the result, and anything
+//! that converts into that float type can be summed, so e.g. `R32`
+//! values can be summed into an `R64`:
+//!
+//! ```
+//! use noisy_float::prelude::*;
+//!
+//! let values = vec![r32(0.1); 10];
+//! let total: R64 = values.iter().copied().sum_compensated();
+//! assert!((total - 1.0).abs() < 1e-7);
+//!
+//! let raw = [1.0e100, 1.0, -1.0e100];
+//! let total: R64 = raw.iter().copied().sum_compensated();
+//! assert_eq!(total, 1.0);
+//! ```
+
+use crate::{FloatChecker, NoisyFloat};
+use core::{fmt, iter::FromIterator, marker::PhantomData};
+use num_traits::float::FloatCore;
+
+/// An accumulator using Kahan summation.
+///
+/// Kahan summation keeps track of the rounding error of the running sum,
+/// so that the error of the result does not grow with the number of values.
+/// Prefer `NeumaierSum` when a value can be much larger than the running sum.
+///
+/// ```
+/// use noisy_float::{prelude::*, sum::KahanSum};
+///
+/// let mut sum = KahanSum::new();
+/// for _ in 0..10 {
+///     sum.add(0.1);
+/// }
+/// let total: R64 = sum.sum();
+/// assert_eq!(total, 1.0);
+/// ```
+pub struct KahanSum<F: FloatCore, C: FloatChecker<F>> {
+    sum: F,
+    compensation: F,
+    overflow: F,
+    checker: PhantomData<C>,
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> KahanSum<F, C> {
+    /// Constructs an accumulator with a sum of zero.
+    #[inline]
+    pub fn new() -> Self {
+        KahanSum {
+            sum: F::zero(),
+            compensation: F::zero(),
+            overflow: F::zero(),
+            checker: PhantomData,
+        }
+    }
+
+    /// Adds a value to the sum.
+    #[inline]
+    pub fn add<V: Into<F>>(&mut self, value: V) {
+        let value = value.into() - self.compensation;
+        let sum = self.sum + value;
+        if overflows(self.sum, value, sum) {
+            self.overflow = self.overflow + scaled(self.sum) + scaled(value);
+            self.sum = F::zero();
+            self.compensation = F::zero();
+            return;
+        }
+        self.compensation = if sum.is_finite() {
+            (sum - self.sum) - value
+        } else {
+            // the compensation would be NaN, while the sum stays infinite or NaN
+            F::zero()
+        };
+        self.sum = sum;
+    }
+
+    /// Returns the sum of the values added so far.
+    ///
+    /// Uses the `FloatChecker` to assert that the sum is valid.
+    #[inline]
+    pub fn sum(&self) -> NoisyFloat<F, C> {
+        NoisyFloat::new(total(self.sum, self.overflow))
+    }
+
+    /// Returns the sum of the values added so far,
+    /// or `None` if the sum is invalid.
+    #[inline]
+    pub fn try_sum(&self) -> Option<NoisyFloat<F, C>> {
+        NoisyFloat::try_new(total(self.sum, self.overflow))
+    }
+}
+
+/// An accumulator using Neumaier's improved Kahan–Babuška summation.
+///
+/// Like `KahanSum`, this keeps track of the rounding error of the running sum,
+/// but it also handles values that are larger than the running sum,
+/// such as in `1e100 + 1.0 - 1e100`.
+///
+/// ```
+/// use noisy_float::{prelude::*, sum::NeumaierSum};
+///
+/// let sum: NeumaierSum<f64, _> = [1.0e100, 1.0, -1.0e100].iter().copied().collect();
+/// assert_eq!(sum.sum(), r64(1.0));
+/// ```
+pub struct NeumaierSum<F: FloatCore, C: FloatChecker<F>> {
+    sum: F,
+    compensation: F,
+    overflow: F,
+    checker: PhantomData<C>,
+}
+
+impl<F: FloatCore, C: FloatChecker<F>> NeumaierSum<F, C> {
+    /// Constructs an accumulator with a sum of zero.
+    #[inline]
+    pub fn new() -> Self {
+        NeumaierSum {
+            sum: F::zero(),
+            compensation: F::zero(),
+            overflow: F::zero(),
+            checker: PhantomData,
+        }
+    }
+
+    /// Adds a value to the sum.
+    #[inline]
+    pub fn add<V: Into<F>>(&mut self, value: V) {
+        let value = value.into();
+        let sum = self.sum + value;
+        if overflows(self.sum, value, sum) {
+            self.overflow = self.overflow + scaled(self.sum) + scaled(value);
+            self.sum = F::zero();
+            return;
+        }
+        if !sum.is_finite() {
+            // the compensation would be NaN, while the sum stays infinite or NaN
+        } else if self.sum.abs() >= value.abs() {
+            self.compensation = self.compensation + ((self.sum - sum) + value);
+        } else {
+            self.compensation = self.compensation + ((value - sum) + self.sum);
+        }
+        self.sum = sum;
+    }
+
+    /// Returns the sum of the values added so far.
+    ///
+    /// Uses the `FloatChecker` to assert that the sum is valid.
+    #[inline]
+    pub fn sum(&self) -> NoisyFloat<F, C> {
+        NoisyFloat::new(total(self.sum + self.compensation, self.overflow))
+    }
+
+    /// Returns the sum of the values added so far,
+    /// or `None` if the sum is invalid.
+    #[inline]
+    pub fn try_sum(&self) -> Option<NoisyFloat<F, C>> {
+        NoisyFloat::try_new(total(self.sum + self.compensation, self.overflow))
+    }
+
+    /// Returns the sum divided by `divisor`, which is finite when the
+    /// quotient is, even if the sum itself overflows.
+    #[inline]
+    pub(crate) fn quotient(&self, divisor: F) -> F {
+        let sum = self.sum + self.compensation;
+        if self.overflow == F::zero() {
+            sum / divisor
+        } else {
+            (self.overflow + scaled(sum)) / scaled(divisor)
+        }
+    }
+
+    /// Returns `dividend` divided by the sum, which is finite when the
+    /// quotient is, even if the sum itself overflows.
+    #[inline]
+    pub(crate) fn divide(&self, dividend: F) -> F {
+        let sum = self.sum + self.compensation;
+        if self.overflow == F::zero() {
+            dividend / sum
+        } else {
+            scaled(dividend) / (self.overflow + scaled(sum))
+        }
+    }
+}
+
+/// Returns whether adding two finite values overflowed to infinity.
+#[inline]
+fn overflows<F: FloatCore>(sum: F, value: F, result: F) -> bool {
+    sum.is_finite() && value.is_finite() && result.is_infinite()
+}
+
+/// Scales a part of the sum that overflowed the running sum down by the
+/// machine epsilon, a power of two, so that it fits in the float type.
+#[inline]
+fn scaled<F: FloatCore>(value: F) -> F {
+    value * F::epsilon()
+}
+
+/// Combines the running sum of an accumulator with the scaled part of the
+/// sum that overflowed it, which is only infinite if the total is.
+#[inline]
+fn total<F: FloatCore>(sum: F, overflow: F) -> F {
+    if overflow == F::zero() {
+        sum
+    } else {
+        (overflow + scaled(sum)) / F::epsilon()
+    }
+}
+
+macro_rules! impl_accumulator_traits {
+    ($name:ident) => {
+        impl<F: FloatCore, C: FloatChecker<F>> Clone for $name<F, C> {
+            #[inline]
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<F: FloatCore, C: FloatChecker<F>> Copy for $name<F, C> {}
+
+        impl<F: FloatCore, C: FloatChecker<F>> Default for $name<F, C> {
+            #[inline]
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<F: FloatCore + fmt::Debug, C: FloatChecker<F>> fmt::Debug for $name<F, C> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+                f.debug_struct(stringify!($name))
+                    .field("sum", &self.sum)
+                    .field("compensation", &self.compensation)
+                    .field("overflow", &self.overflow)
+                    .finish()
+            }
+        }
+
+        impl<F: FloatCore, C: FloatChecker<F>, V: Into<F>> Extend<V> for $name<F, C> {
+            #[inline]
+            fn extend<I: IntoIterator<Item = V>>(&mut self, iter: I) {
+                for value in iter {
+                    self.add(value);
+                }
+            }
+        }
+
+        impl<F: FloatCore, C: FloatChecker<F>, V: Into<F>> FromIterator<V> for $name<F, C> {
+            #[inline]
+            fn from_iter<I: IntoIterator<Item = V>>(iter: I) -> Self {
+                let mut sum = Self::new();
+                sum.extend(iter);
+                sum
+            }
+        }
+    };
+}
+
+impl_accumulator_traits!(KahanSum);
+impl_accumulator_traits!(NeumaierSum);
+
+/// The number of values that pairwise summation adds up directly.
+const PAIRWISE_BLOCK: usize = 32;
+
+/// Extension methods for accurately summing iterators of floats.
+///
+/// This is implemented for every iterator, and its methods can be used
+/// when the items convert into the float type of the result, such as
+/// `NoisyFloat` values, raw floats, or `f32` based values summed into
+/// an `f64` based result.
+pub trait SumExt: Iterator + Sized {
+    /// Sums the values using `NeumaierSum`.
+    ///
+    /// Uses the `FloatChecker` to assert that the sum is valid.
+    fn sum_compensated<F, C>(self) -> NoisyFloat<F, C>
+    where
+        Self::Item: Into<F>,
+        F: FloatCore,
+        C: FloatChecker<F>,
+    {
+        self.collect::<NeumaierSum<F, C>>().sum()
+    }
+
+    /// Sums the values by recursively summing the halves of the sequence,
+    /// which makes the rounding error grow only logarithmically with the
+    /// number of values, without the cost of compensated summation.
+    ///
+    /// This does not allocate; the partial sums are kept on the stack.
+    /// Uses the `FloatChecker` to assert that the sum is valid.
+    /// Unlike `sum_compensated`, this does not handle partial sums that
+    /// overflow, even if the total would be within range.
+    fn sum_pairwise<F, C>(mut self) -> NoisyFloat<F, C>
+    where
+        Self::Item: Into<F>,
+        F: FloatCore,
+        C: FloatChecker<F>,
+    {
+        // `stack[i]` holds the sum of `2^levels[i]` blocks, with decreasing
+        // levels, like the digits of a binary counter of the blocks.
+        let mut stack = [F::zero(); usize::BITS as usize];
+        let mut levels = [0; usize::BITS as usize];
+        let mut len = 0;
+        loop {
+            let mut block = F::zero();
+            let mut count = 0;
+            for value in self.by_ref().take(PAIRWISE_BLOCK) {
+                block = block + value.into();
+                count += 1;
+            }
+            if count == 0 {
+                break;
+            }
+            let mut level = 0;
+            while len > 0 && levels[len - 1] == level {
+                len -= 1;
+                block = stack[len] + block;
+                level += 1;
+            }
+            stack[len] = block;
+            levels[len] = level;
+            len += 1;
+            if count < PAIRWISE_BLOCK {
+                break;
+            }
+        }
+        let total = stack[..len]
+            .iter()
+            .rev()
+            .fold(F::zero(), |total, &partial| partial + total);
+        NoisyFloat::new(total)
+    }
+}
+
+impl<I: Iterator> SumExt for I {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    #[test]
+    fn kahan() {
+        let mut sum = KahanSum::<f64, _>::new();
+        for _ in 0..10 {
+            sum.add(0.1);
+        }
+        assert_eq!(sum.sum(), r64(1.0));
+        assert_eq!(sum.try_sum(), Some(r64(1.0)));
+
+        let mut naive = 0.0;
+        for _ in 0..10 {
+            naive += 0.1;
+        }
+        assert_ne!(naive, 1.0);
+    }
+
+    #[test]
+    fn neumaier() {
+        let values = [1.0, 1.0e100, 1.0, -1.0e100];
+        let sum: NeumaierSum<f64, _> = values.iter().copied().collect();
+        assert_eq!(sum.sum(), r64(2.0));
+        let sum: KahanSum<f64, _> = values.iter().copied().collect();
+        assert_eq!(sum.try_sum(), Some(r64(0.0)));
+
+        let mut sum = NeumaierSum::<f64, _>::default();
+        sum.extend([r64(0.5), r64(0.25)].iter().copied());
+        sum.add(0.25f32);
+        assert_eq!(sum.sum(), r64(1.0));
+    }
+
+    #[test]
+    fn invalid_sum() {
+        let sum: NeumaierSum<f64, crate::checkers::FiniteChecker> =
+            [f64::MAX, f64::MAX].iter().copied().collect();
+        assert_eq!(sum.try_sum(), None);
+        let sum: KahanSum<f32, crate::checkers::NumChecker> =
+            [f32::INFINITY, 1.0].iter().copied().collect();
+        assert_eq!(sum.try_sum(), Some(N32::INFINITY));
+        let sum: NeumaierSum<f32, crate::checkers::NumChecker> =
+            [1.0, f32::NEG_INFINITY, 1.0].iter().copied().collect();
+        assert_eq!(sum.try_sum(), Some(N32::NEG_INFINITY));
+    }
+
+    #[test]
+    fn intermediate_overflow() {
+        let values = [R64::MAX, R64::MAX, R64::MIN];
+        let sum: NeumaierSum<f64, _> = values.iter().copied().collect();
+        assert_eq!(sum.sum(), R64::MAX);
+        let sum: KahanSum<f64, _> = values.iter().copied().collect();
+        assert_eq!(sum.sum(), R64::MAX);
+        let total: R64 = values.iter().copied().sum_compensated();
+        assert_eq!(total, R64::MAX);
+
+        let values = [f64::MAX, f64::MAX, 1.0, -f64::MAX, -f64::MAX, 0.5];
+        let sum: NeumaierSum<f64, crate::checkers::FiniteChecker> =
+            values.iter().copied().collect();
+        assert_eq!(sum.sum(), r64(1.5));
+
+        // the total itself overflows
+        let sum: KahanSum<f64, crate::checkers::FiniteChecker> =
+            [f64::MAX, f64::MAX, f64::MAX, -f64::MAX]
+                .iter()
+                .copied()
+                .collect();
+        assert_eq!(sum.try_sum(), None);
+
+        // pairwise summation overflows in the partial sums
+        let total: N64 = [f64::MAX, f64::MAX, -f64::MAX]
+            .iter()
+            .copied()
+            .sum_pairwise();
+        assert_eq!(total, N64::INFINITY);
+    }
+
+    #[test]
+    fn mixed_precision() {
+        let values = [r32(0.1); 1000];
+        let total: R64 = values.iter().copied().sum_compensated();
+        assert_eq!(total, 1000.0 * 0.1f32 as f64);
+        let total: R64 = values.iter().copied().sum_pairwise();
+        assert!((total - 1000.0 * 0.1f32 as f64).abs() < 1e-9);
+        let total: R32 = values.iter().copied().sum_compensated();
+        assert_eq!(total, r32(100.0));
+    }
+
+    #[test]
+    fn pairwise() {
+        let empty: R64 = core::iter::empty::<f64>().sum_pairwise();
+        assert_eq!(empty, 0.0);
+        for &len in &[1, 31, 32, 33, 64, 1000, 4097] {
+            let total: N64 = (1..=len).map(|i| i as f64).sum_pairwise();
+            assert_eq!(total, (len * (len + 1) / 2) as f64);
+        }
+        let total: R32 = (0..1_000_000).map(|_| 0.1f32).sum_pairwise();
+        let naive: f32 = (0..1_000_000).map(|_| 0.1f32).sum();
+        assert!((total - 100_000.0).abs() < 1.0);
+        assert!((naive - 100_000.0).abs() > 100.0);
+    }
+}
@@ -17,10 +17,46 @@
 //! Definitions in this module all use `debug_assert!`
 //! to check for valid values, so there is no overhead
 //! when running in an optimized build.
+//!
+//! Each type also has associated constants such as `R64::PI`,
+//! and a `const_new` constructor that can be used in constants and statics.
+//! An invalid value passed to `const_new` is a compilation error there.
+//!
+//! ```
+//! use noisy_float::prelude::*;
+//!
+//! const GRAVITY: R64 = R64::const_new(9.81);
+//! static BOUNDS: [N32; 2] = [N32::NEG_INFINITY, N32::ZERO];
+//!
+//! assert_eq!(GRAVITY * 2.0, 19.62);
+//! assert!(BOUNDS[0] < BOUNDS[1]);
+//! ```
+//!
+//! ```compile_fail
+//! use noisy_float::prelude::*;
+//!
+//! const TOO_LARGE: R64 = R64::const_new(f64::MAX * 2.0);
+//! ```
+//!
+//! The `n32!`, `n64!`, `r32!` and `r64!` macros are shorthands for this,
+//! which can be used in any expression.
+//!
+//! ```
+//! use noisy_float::prelude::*;
+//!
+//! let threshold = r64!(0.3);
+//! assert_eq!(threshold, r64(0.3));
+//! ```
+//!
+//! ```compile_fail
+//! use noisy_float::prelude::*;
+//!
+//! let threshold = r64!(f64::NAN);
+//! ```
 
 use crate::{
     checkers::{FiniteChecker, NumChecker},
-    NoisyFloat,
+    AtomicNoisyFloat, NoisyFloat, OptionalFloat,
 };
 
 /// A floating point number behaving like `f32` that does not allow NaN.
@@ -47,6 +83,82 @@ pub type R32 = NoisyFloat<f32, FiniteChecker>;
 /// numbers do not include NaN or +/- Infinity.
 pub type R64 = NoisyFloat<f64, FiniteChecker>;
 
+/// An optional `N32` with the same size as `f32`, storing `None` as NaN.
+pub type OptN32 = OptionalFloat<f32, NumChecker>;
+
+/// An optional `N64` with the same size as `f64`, storing `None` as NaN.
+pub type OptN64 = OptionalFloat<f64, NumChecker>;
+
+/// An optional `R32` with the same size as `f32`, storing `None` as NaN.
+pub type OptR32 = OptionalFloat<f32, FiniteChecker>;
+
+/// An optional `R64` with the same size as `f64`, storing `None` as NaN.
+pub type OptR64 = OptionalFloat<f64, FiniteChecker>;
+
+/// An `N32` which can be safely shared between threads.
+#[cfg(target_has_atomic = "32")]
+pub type AtomicN32 = AtomicNoisyFloat<f32, NumChecker>;
+
+/// An `N64` which can be safely shared between threads.
+#[cfg(target_has_atomic = "64")]
+pub type AtomicN64 = AtomicNoisyFloat<f64, NumChecker>;
+
+/// An `R32` which can be safely shared between threads.
+#[cfg(target_has_atomic = "32")]
+pub type AtomicR32 = AtomicNoisyFloat<f32, FiniteChecker>;
+
+/// An `R64` which can be safely shared between threads.
+#[cfg(target_has_atomic = "64")]
+pub type AtomicR64 = AtomicNoisyFloat<f64, FiniteChecker>;
+
+/// Constructs a `N32` constant, checking at compile time that the value is not NaN.
+///
+/// The argument must be a constant expression, such as a literal.
+/// This expands to `N32::const_new(value)` evaluated in a `const` context.
+#[macro_export]
+macro_rules! n32 {
+    ($value:expr) => {{
+        const VALUE: $crate::types::N32 = $crate::types::N32::const_new($value);
+        VALUE
+    }};
+}
+
+/// Constructs a `N64` constant, checking at compile time that the value is not NaN.
+///
+/// The argument must be a constant expression, such as a literal.
+/// This expands to `N64::const_new(value)` evaluated in a `const` context.
+#[macro_export]
+macro_rules! n64 {
+    ($value:expr) => {{
+        const VALUE: $crate::types::N64 = $crate::types::N64::const_new($value);
+        VALUE
+    }};
+}
+
+/// Constructs a `R32` constant, checking at compile time that the value is finite.
+///
+/// The argument must be a constant expression, such as a literal.
+/// This expands to `R32::const_new(value)` evaluated in a `const` context.
+#[macro_export]
+macro_rules! r32 {
+    ($value:expr) => {{
+        const VALUE: $crate::types::R32 = $crate::types::R32::const_new($value);
+        VALUE
+    }};
+}
+
+/// Constructs a `R64` constant, checking at compile time that the value is finite.
+///
+/// The argument must be a constant expression, such as a literal.
+/// This expands to `R64::const_new(value)` evaluated in a `const` context.
+#[macro_export]
+macro_rules! r64 {
+    ($value:expr) => {{
+        const VALUE: $crate::types::R64 = $crate::types::R64::const_new($value);
+        VALUE
+    }};
+}
+
 /// Shorthand for `N32::new(value)`.
 #[inline]
 pub fn n32(value: f32) -> N32 {
@@ -70,3 +182,129 @@ pub fn r32(value: f32) -> R32 {
 pub fn r64(value: f64) -> R64 {
     R64::new(value)
 }
+
+macro_rules! impl_const_new {
+    ($name:ident, $float:ident, |$value:ident| $check:expr, $message:literal) => {
+        impl $name {
+            /// Constructs a value in a `const` context.
+            ///
+            /// The value is validated like in `new`, but with `assert!`
+            /// regardless of the build profile, so an invalid value in a
+            /// `const` or `static` initializer fails compilation.
+            #[inline]
+            pub const fn const_new($value: $float) -> Self {
+                assert!($check, $message);
+                Self::new_unchecked($value)
+            }
+        }
+    };
+}
+
+impl_const_new!(N32, f32, |value| !value.is_nan(), "unexpected NaN");
+impl_const_new!(N64, f64, |value| !value.is_nan(), "unexpected NaN");
+impl_const_new!(
+    R32,
+    f32,
+    |value| value.is_finite(),
+    "unexpected NaN or infinity"
+);
+impl_const_new!(
+    R64,
+    f64,
+    |value| value.is_finite(),
+    "unexpected NaN or infinity"
+);
+
+macro_rules! impl_consts {
+    ($name:ident, $float:ident) => {
+        impl $name {
+            /// The value `0.0`.
+            pub const ZERO: Self = Self::new_unchecked(0.0);
+            /// The value `1.0`.
+            pub const ONE: Self = Self::new_unchecked(1.0);
+            /// Archimedes' constant (π).
+            pub const PI: Self = Self::new_unchecked(core::$float::consts::PI);
+            /// Euler's number (e).
+            pub const E: Self = Self::new_unchecked(core::$float::consts::E);
+            /// Machine epsilon, the difference between `1.0` and the next larger representable number.
+            pub const EPSILON: Self = Self::new_unchecked(<$float>::EPSILON);
+            /// The largest finite value.
+            pub const MAX: Self = Self::new_unchecked(<$float>::MAX);
+            /// The smallest finite value.
+            pub const MIN: Self = Self::new_unchecked(<$float>::MIN);
+            /// The smallest positive normal value.
+            pub const MIN_POSITIVE: Self = Self::new_unchecked(<$float>::MIN_POSITIVE);
+        }
+    };
+}
+
+impl_consts!(N32, f32);
+impl_consts!(N64, f64);
+impl_consts!(R32, f32);
+impl_consts!(R64, f64);
+
+macro_rules! impl_infinite_consts {
+    ($name:ident, $float:ident) => {
+        impl $name {
+            /// Positive infinity.
+            pub const INFINITY: Self = Self::new_unchecked(<$float>::INFINITY);
+            /// Negative infinity.
+            pub const NEG_INFINITY: Self = Self::new_unchecked(<$float>::NEG_INFINITY);
+        }
+    };
+}
+
+impl_infinite_consts!(N32, f32);
+impl_infinite_consts!(N64, f64);
+
+/// A half-precision floating point number that does not allow NaN.
+///
+/// Requires the `half` feature.
+#[cfg(feature = "half")]
+pub type N16 = NoisyFloat<half::f16, NumChecker>;
+
+/// A half-precision floating point number that does not allow NaN or +/- Infinity.
+///
+/// Requires the `half` feature.
+#[cfg(feature = "half")]
+pub type R16 = NoisyFloat<half::f16, FiniteChecker>;
+
+/// A bfloat16 floating point number that does not allow NaN.
+///
+/// Requires the `half` feature.
+#[cfg(feature = "half")]
+pub type NB16 = NoisyFloat<half::bf16, NumChecker>;
+
+/// A bfloat16 floating point number that does not allow NaN or +/- Infinity.
+///
+/// Requires the `half` feature.
+#[cfg(feature = "half")]
+pub type RB16 = NoisyFloat<half::bf16, FiniteChecker>;
+
+/// Shorthand for `N16::new(value)`.
+#[cfg(feature = "half")]
+#[inline]
+pub fn n16(value: half::f16) -> N16 {
+    N16::new(value)
+}
+
+/// Shorthand for `R16::new(value)`.
+#[cfg(feature = "half")]
+#[inline]
+pub fn r16(value: half::f16) -> R16 {
+    R16::new(value)
+}
+
+/// Shorthand for `NB16::new(value)`.
+#[cfg(feature = "half")]
+#[inline]
+pub fn nb16(value: half::bf16) -> NB16 {
+    NB16::new(value)
+}
+
+/// Shorthand for `RB16::new(value)`.
+#[cfg(feature = "half")]
+#[inline]
+pub fn rb16(value: half::bf16) -> RB16 {
+    RB16::new(value)
+}
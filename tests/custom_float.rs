@@ -0,0 +1,465 @@
+// Copyright 2016-2019 Matthew D. Michelotti
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Exercises the API of `NoisyFloat` over a float type defined outside of this crate.
+
+#![cfg(any(feature = "std", feature = "libm"))]
+
+use noisy_float::{
+    checkers::{FiniteChecker, NumChecker},
+    impl_float_conversions, NoisyFloat, OptionalFloat, OrderedKey, RawFloat,
+};
+use num_traits::{
+    float::FloatCore, Bounded, Float, FloatConst, FromPrimitive, Num, NumCast, One, Signed,
+    ToPrimitive, Zero,
+};
+use std::{
+    collections::HashSet,
+    convert::TryFrom,
+    num::FpCategory,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign},
+};
+
+/// A mock float type, standing in for a software float or `half::f16`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+struct Soft(f64);
+
+type RSoft = NoisyFloat<Soft, FiniteChecker>;
+type NSoft = NoisyFloat<Soft, NumChecker>;
+
+fn rs(value: f64) -> RSoft {
+    RSoft::new(Soft(value))
+}
+
+macro_rules! impl_ops {
+    ($($trait:ident $method:ident $assign_trait:ident $assign_method:ident),*) => {$(
+        impl $trait for Soft {
+            type Output = Soft;
+            fn $method(self, rhs: Soft) -> Soft {
+                Soft(self.0.$method(rhs.0))
+            }
+        }
+
+        impl $assign_trait for Soft {
+            fn $assign_method(&mut self, rhs: Soft) {
+                self.0.$assign_method(rhs.0)
+            }
+        }
+    )*};
+}
+
+impl_ops!(
+    Add add AddAssign add_assign,
+    Sub sub SubAssign sub_assign,
+    Mul mul MulAssign mul_assign,
+    Div div DivAssign div_assign,
+    Rem rem RemAssign rem_assign
+);
+
+impl Neg for Soft {
+    type Output = Soft;
+    fn neg(self) -> Soft {
+        Soft(-self.0)
+    }
+}
+
+impl Zero for Soft {
+    fn zero() -> Soft {
+        Soft(0.0)
+    }
+    fn is_zero(&self) -> bool {
+        self.0 == 0.0
+    }
+}
+
+impl One for Soft {
+    fn one() -> Soft {
+        Soft(1.0)
+    }
+}
+
+impl Num for Soft {
+    type FromStrRadixErr = <f64 as Num>::FromStrRadixErr;
+    fn from_str_radix(str: &str, radix: u32) -> Result<Soft, Self::FromStrRadixErr> {
+        f64::from_str_radix(str, radix).map(Soft)
+    }
+}
+
+impl ToPrimitive for Soft {
+    fn to_i64(&self) -> Option<i64> {
+        self.0.to_i64()
+    }
+    fn to_u64(&self) -> Option<u64> {
+        self.0.to_u64()
+    }
+    fn to_f64(&self) -> Option<f64> {
+        Some(self.0)
+    }
+}
+
+impl NumCast for Soft {
+    fn from<T: ToPrimitive>(n: T) -> Option<Soft> {
+        n.to_f64().map(Soft)
+    }
+}
+
+impl FromPrimitive for Soft {
+    fn from_i64(n: i64) -> Option<Soft> {
+        Some(Soft(n as f64))
+    }
+    fn from_u64(n: u64) -> Option<Soft> {
+        Some(Soft(n as f64))
+    }
+}
+
+impl Signed for Soft {
+    fn abs(&self) -> Soft {
+        Soft(self.0.abs())
+    }
+    fn abs_sub(&self, other: &Soft) -> Soft {
+        Soft(Signed::abs_sub(&self.0, &other.0))
+    }
+    fn signum(&self) -> Soft {
+        Soft(self.0.signum())
+    }
+    fn is_positive(&self) -> bool {
+        Signed::is_positive(&self.0)
+    }
+    fn is_negative(&self) -> bool {
+        Signed::is_negative(&self.0)
+    }
+}
+
+impl Bounded for Soft {
+    fn min_value() -> Soft {
+        Soft(f64::MIN)
+    }
+    fn max_value() -> Soft {
+        Soft(f64::MAX)
+    }
+}
+
+impl RawFloat for Soft {
+    type Bits = u64;
+    fn to_bits(self) -> u64 {
+        self.0.to_bits()
+    }
+    fn from_bits(bits: u64) -> Soft {
+        Soft(f64::from_bits(bits))
+    }
+}
+
+impl_float_conversions!(Soft);
+
+impl FloatCore for Soft {
+    fn infinity() -> Soft {
+        Soft(f64::INFINITY)
+    }
+    fn neg_infinity() -> Soft {
+        Soft(f64::NEG_INFINITY)
+    }
+    fn nan() -> Soft {
+        Soft(f64::NAN)
+    }
+    fn neg_zero() -> Soft {
+        Soft(-0.0)
+    }
+    fn min_value() -> Soft {
+        Soft(f64::MIN)
+    }
+    fn min_positive_value() -> Soft {
+        Soft(f64::MIN_POSITIVE)
+    }
+    fn epsilon() -> Soft {
+        Soft(f64::EPSILON)
+    }
+    fn max_value() -> Soft {
+        Soft(f64::MAX)
+    }
+    fn classify(self) -> FpCategory {
+        self.0.classify()
+    }
+    fn to_degrees(self) -> Soft {
+        Soft(self.0.to_degrees())
+    }
+    fn to_radians(self) -> Soft {
+        Soft(self.0.to_radians())
+    }
+    fn integer_decode(self) -> (u64, i16, i8) {
+        FloatCore::integer_decode(self.0)
+    }
+}
+
+macro_rules! delegate {
+    ($($method:ident($($arg:ident),*) -> $ret:ident;)*) => {$(
+        fn $method(self $(, $arg: Soft)*) -> delegate!(@ret $ret) {
+            delegate!(@wrap $ret, self.0.$method($($arg.0),*))
+        }
+    )*};
+    (@ret Soft) => { Soft };
+    (@ret bool) => { bool };
+    (@wrap Soft, $e:expr) => { Soft($e) };
+    (@wrap bool, $e:expr) => { $e };
+}
+
+#[allow(deprecated)]
+impl Float for Soft {
+    fn nan() -> Soft {
+        Soft(f64::NAN)
+    }
+    fn infinity() -> Soft {
+        Soft(f64::INFINITY)
+    }
+    fn neg_infinity() -> Soft {
+        Soft(f64::NEG_INFINITY)
+    }
+    fn neg_zero() -> Soft {
+        Soft(-0.0)
+    }
+    fn min_value() -> Soft {
+        Soft(f64::MIN)
+    }
+    fn min_positive_value() -> Soft {
+        Soft(f64::MIN_POSITIVE)
+    }
+    fn max_value() -> Soft {
+        Soft(f64::MAX)
+    }
+    fn classify(self) -> FpCategory {
+        self.0.classify()
+    }
+    fn powi(self, n: i32) -> Soft {
+        Soft(self.0.powi(n))
+    }
+    fn sin_cos(self) -> (Soft, Soft) {
+        let (sin, cos) = self.0.sin_cos();
+        (Soft(sin), Soft(cos))
+    }
+    fn integer_decode(self) -> (u64, i16, i8) {
+        Float::integer_decode(self.0)
+    }
+    delegate! {
+        is_nan() -> bool;
+        is_infinite() -> bool;
+        is_finite() -> bool;
+        is_normal() -> bool;
+        is_sign_positive() -> bool;
+        is_sign_negative() -> bool;
+        floor() -> Soft;
+        ceil() -> Soft;
+        round() -> Soft;
+        trunc() -> Soft;
+        fract() -> Soft;
+        abs() -> Soft;
+        signum() -> Soft;
+        mul_add(a, b) -> Soft;
+        recip() -> Soft;
+        powf(n) -> Soft;
+        sqrt() -> Soft;
+        exp() -> Soft;
+        exp2() -> Soft;
+        ln() -> Soft;
+        log(base) -> Soft;
+        log2() -> Soft;
+        log10() -> Soft;
+        max(other) -> Soft;
+        min(other) -> Soft;
+        abs_sub(other) -> Soft;
+        cbrt() -> Soft;
+        hypot(other) -> Soft;
+        sin() -> Soft;
+        cos() -> Soft;
+        tan() -> Soft;
+        asin() -> Soft;
+        acos() -> Soft;
+        atan() -> Soft;
+        atan2(other) -> Soft;
+        exp_m1() -> Soft;
+        ln_1p() -> Soft;
+        sinh() -> Soft;
+        cosh() -> Soft;
+        tanh() -> Soft;
+        asinh() -> Soft;
+        acosh() -> Soft;
+        atanh() -> Soft;
+    }
+}
+
+impl FloatConst for Soft {
+    fn E() -> Soft {
+        Soft(f64::E())
+    }
+    fn FRAC_1_PI() -> Soft {
+        Soft(f64::FRAC_1_PI())
+    }
+    fn FRAC_1_SQRT_2() -> Soft {
+        Soft(f64::FRAC_1_SQRT_2())
+    }
+    fn FRAC_2_PI() -> Soft {
+        Soft(f64::FRAC_2_PI())
+    }
+    fn FRAC_2_SQRT_PI() -> Soft {
+        Soft(f64::FRAC_2_SQRT_PI())
+    }
+    fn FRAC_PI_2() -> Soft {
+        Soft(f64::FRAC_PI_2())
+    }
+    fn FRAC_PI_3() -> Soft {
+        Soft(f64::FRAC_PI_3())
+    }
+    fn FRAC_PI_4() -> Soft {
+        Soft(f64::FRAC_PI_4())
+    }
+    fn FRAC_PI_6() -> Soft {
+        Soft(f64::FRAC_PI_6())
+    }
+    fn FRAC_PI_8() -> Soft {
+        Soft(f64::FRAC_PI_8())
+    }
+    fn LN_10() -> Soft {
+        Soft(f64::LN_10())
+    }
+    fn LN_2() -> Soft {
+        Soft(f64::LN_2())
+    }
+    fn LOG10_E() -> Soft {
+        Soft(f64::LOG10_E())
+    }
+    fn LOG2_E() -> Soft {
+        Soft(f64::LOG2_E())
+    }
+    fn PI() -> Soft {
+        Soft(f64::PI())
+    }
+    fn SQRT_2() -> Soft {
+        Soft(f64::SQRT_2())
+    }
+}
+
+#[test]
+fn construction() {
+    assert_eq!(RSoft::new(Soft(1.5)).raw(), Soft(1.5));
+    assert_eq!(RSoft::try_new(Soft(f64::INFINITY)), None);
+    assert_eq!(NSoft::try_new(Soft(f64::NAN)), None);
+    assert_eq!(RSoft::from_f32(2.0), rs(2.0));
+    assert_eq!(RSoft::from_f64(2.0), rs(2.0));
+    assert_eq!(RSoft::try_from_f64(f64::INFINITY), None);
+    assert_eq!(
+        NSoft::try_from_f64(f64::INFINITY),
+        Some(NSoft::new(Soft(f64::INFINITY)))
+    );
+    assert_eq!(RSoft::borrowed(&Soft(3.0)), &rs(3.0));
+    assert_eq!(RSoft::try_borrowed(&Soft(f64::NAN)), None);
+    assert_eq!(RSoft::new_unchecked(Soft(4.0)), rs(4.0));
+    assert_eq!(RSoft::default(), rs(0.0));
+}
+
+#[test]
+fn conversions() {
+    let raw: Soft = rs(1.5).into();
+    assert_eq!(raw, Soft(1.5));
+    assert_eq!(RSoft::try_from(Soft(2.5)), Ok(rs(2.5)));
+    assert!(RSoft::try_from(Soft(f64::INFINITY)).is_err());
+    let number: NSoft = rs(1.0).into();
+    assert_eq!(number, NSoft::new(Soft(1.0)));
+    assert_eq!(rs(7.9).to_i32(), Some(7));
+    assert_eq!(RSoft::from_u8(3), Some(rs(3.0)));
+    assert_eq!(<RSoft as NumCast>::from(5u16), Some(rs(5.0)));
+    assert_eq!(RSoft::from_str_radix("2.5", 10).ok(), Some(rs(2.5)));
+}
+
+#[test]
+fn arithmetic() {
+    let mut value = rs(6.0);
+    value += rs(2.0);
+    value -= Soft(1.0);
+    value *= rs(2.0);
+    value /= Soft(7.0);
+    value %= rs(1.5);
+    assert_eq!(value, rs(0.5));
+    assert_eq!(rs(1.0) + rs(2.0) * rs(3.0) - Soft(1.0), rs(6.0));
+    assert_eq!(-rs(2.0) / rs(4.0), rs(-0.5));
+    assert_eq!(rs(5.0) % Soft(3.0), rs(2.0));
+    assert_eq!(
+        [rs(1.0), rs(2.0), rs(3.0)].iter().copied().sum::<RSoft>(),
+        rs(6.0)
+    );
+    assert_eq!([rs(2.0), rs(3.0)].iter().product::<RSoft>(), rs(6.0));
+}
+
+#[test]
+fn ordering_and_hashing() {
+    let mut values = [rs(3.0), rs(-1.0), rs(2.0)];
+    values.sort();
+    assert_eq!(values, [rs(-1.0), rs(2.0), rs(3.0)]);
+    assert_eq!(rs(1.0).min(rs(2.0)), rs(1.0));
+    assert_eq!(rs(1.0).max(rs(2.0)), rs(2.0));
+    assert_eq!(rs(5.0).clamp(rs(0.0), rs(1.0)), rs(1.0));
+    assert!(rs(1.0) < Soft(2.0));
+
+    let set: HashSet<RSoft> = [rs(0.0), rs(-0.0), rs(1.0)].iter().copied().collect();
+    assert_eq!(set.len(), 2);
+}
+
+#[test]
+fn math() {
+    assert_eq!(rs(-2.0).abs(), rs(2.0));
+    assert_eq!(rs(9.0).sqrt(), rs(3.0));
+    assert_eq!(rs(2.0).powi(3), rs(8.0));
+    assert_eq!(rs(0.0).exp(), rs(1.0));
+    assert_eq!(rs(3.0).hypot(rs(4.0)), rs(5.0));
+    assert_eq!(Float::sqrt(rs(16.0)), rs(4.0));
+    assert_eq!(FloatCore::floor(rs(1.5)), rs(1.0));
+    assert_eq!(<RSoft as FloatConst>::PI(), rs(std::f64::consts::PI));
+    assert_eq!(Signed::abs(&rs(-1.0)), rs(1.0));
+    assert_eq!(<RSoft as Bounded>::max_value(), rs(f64::MAX));
+    assert_eq!(
+        <NSoft as FloatCore>::infinity(),
+        NSoft::new(Soft(f64::INFINITY))
+    );
+    assert_eq!(<RSoft as FloatCore>::epsilon(), rs(f64::EPSILON));
+}
+
+#[test]
+fn bytes() {
+    assert_eq!(rs(1.5).to_le_bytes(), 1.5f64.to_le_bytes());
+    assert_eq!(RSoft::try_from_be_bytes(2.5f64.to_be_bytes()), Ok(rs(2.5)));
+    assert!(RSoft::try_from_ne_bytes(f64::INFINITY.to_ne_bytes()).is_err());
+    assert!(rs(-1.0).to_ordered_bytes() < rs(0.5).to_ordered_bytes());
+    assert_eq!(RSoft::try_from(OrderedKey::from(rs(-2.0))), Ok(rs(-2.0)));
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn bytes_bulk() {
+    assert_eq!(
+        RSoft::try_vec_from_le_bytes(&0.25f64.to_le_bytes()),
+        Ok(vec![rs(0.25)])
+    );
+}
+
+#[test]
+fn optional() {
+    let raw = [Soft(1.0), Soft(f64::NAN)];
+    let values = OptionalFloat::<Soft, FiniteChecker>::try_from_raw_slice(&raw).unwrap();
+    assert_eq!(values[0].get(), Some(rs(1.0)));
+    assert_eq!(values[1].get(), None);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic]
+fn invalid_arithmetic() {
+    let _ = rs(f64::MAX) * rs(2.0);
+}